@@ -1,6 +1,9 @@
+use clap::ValueHint;
+use std::collections::BTreeMap;
 use std::fmt::{Display, Formatter};
 use wasm_instrument::parity_wasm::elements::{
-    BlockType, FuncBody, Instruction, Instructions, Module,
+    BlockType, External, FuncBody, FunctionType, InitExpr, Instruction, Instructions, Local,
+    Module, ValueType,
 };
 
 use super::injector::FunctionMapper;
@@ -12,13 +15,13 @@ use super::injector::FunctionMapper;
 ///
 /// ```
 /// use std::path::Path;
-/// use wasm_injector::{ Injection, util::load_module_from_wasm };
+/// use wasm_injector::{ Injection, InjectionOptions, util::load_module_from_wasm };
 ///
 /// # fn main() -> Result<(), String> {
 /// let source = Path::new("samples/example.wasm");
 /// let mut module = load_module_from_wasm(source)?;   
 /// let injection = Injection::InfiniteLoop;
-/// injection.inject(&mut module, "validate_block", None)?;
+/// injection.inject(&mut module, "validate_block", &InjectionOptions::default())?;
 /// # Ok(())
 /// # }
 /// ```
@@ -30,6 +33,631 @@ pub enum Injection {
     StackOverflow,
     Noops,
     HeapOverflow,
+    Unreachable,
+    ArithmeticTrap,
+    StackExhaustion,
+    MemoryGrowExhaustion,
+    IndirectCallFault,
+    BoundedLoop,
+    BadPointerLength,
+    BadValidationResult,
+    HostFunctionMisuse,
+    AllocatorMisuse,
+    AllocatorHeaderCorruption,
+    AllocatorFragmentation,
+    StorageWriteSpam,
+    StorageProofMiss,
+    LoggingSpam,
+    HostCpuBurn,
+    ForbiddenHostCall,
+    RuntimePanic,
+    StackPointerCorruption,
+    OutOfBoundsAccess,
+}
+
+/// # Injection options
+///
+/// Parameters of the injections that need them. Each injection ignores the options it doesn't use.
+#[derive(clap::Args, Default, PartialEq, Eq, Clone, Debug)]
+#[command(about = None, long_about = None)]
+pub struct InjectionOptions {
+    #[arg(
+        long,
+        value_name = "size",
+        help = "The number of noops to be injected in MB (1 NOP = 1 byte)",
+        value_hint = ValueHint::Other
+    )]
+    pub size: Option<i16>,
+
+    #[arg(
+        long,
+        value_enum,
+        value_name = "trap",
+        help = "The arithmetic trap to be injected",
+        value_hint = ValueHint::Other
+    )]
+    pub trap: Option<ArithmeticTrap>,
+
+    #[arg(
+        long,
+        value_name = "locals",
+        help = "The number of additional locals used to inflate the stack frame of the recursive function",
+        value_hint = ValueHint::Other
+    )]
+    pub locals: Option<u32>,
+
+    #[arg(
+        long,
+        value_enum,
+        value_name = "indirect-call-fault",
+        help = "The fault of the injected indirect call",
+        value_hint = ValueHint::Other
+    )]
+    pub indirect_call_fault: Option<IndirectCallFault>,
+
+    #[arg(
+        long,
+        value_name = "iterations",
+        help = "The number of iterations of the injected loop",
+        value_hint = ValueHint::Other
+    )]
+    pub iterations: Option<u32>,
+
+    #[arg(
+        long,
+        value_enum,
+        value_name = "pointer-length-fault",
+        help = "The fault of the returned pointer and length",
+        value_hint = ValueHint::Other
+    )]
+    pub pointer_length_fault: Option<PointerLengthFault>,
+
+    #[arg(
+        long,
+        value_enum,
+        value_name = "validation-result-fault",
+        help = "The semantic fault of the returned `ValidationResult`",
+        value_hint = ValueHint::Other
+    )]
+    pub validation_result_fault: Option<ValidationResultFault>,
+
+    #[arg(
+        long,
+        value_name = "upward-messages",
+        help = "The number of upward messages of the `too-many-upward-messages` fault, 16384 at most (default: 16384)",
+        value_hint = ValueHint::Other
+    )]
+    pub upward_messages: Option<u32>,
+
+    #[arg(
+        long,
+        value_name = "host-function",
+        help = "The name of the imported host function to be called, e.g. `ext_storage_get_version_1`",
+        value_hint = ValueHint::Other
+    )]
+    pub host_function: Option<String>,
+
+    #[arg(
+        long,
+        value_enum,
+        value_name = "host-function-misuse",
+        help = "The kind of invalid memory spans passed to the host function",
+        value_hint = ValueHint::Other
+    )]
+    pub host_function_misuse: Option<HostFunctionMisuse>,
+
+    #[arg(
+        long,
+        value_enum,
+        value_name = "allocator-misuse",
+        help = "The invalid sequence of allocator calls",
+        value_hint = ValueHint::Other
+    )]
+    pub allocator_misuse: Option<AllocatorMisuse>,
+
+    #[arg(
+        long,
+        value_enum,
+        value_name = "header-corruption",
+        help = "The pattern written over the allocation header",
+        value_hint = ValueHint::Other
+    )]
+    pub header_corruption: Option<HeaderCorruption>,
+
+    #[arg(
+        long,
+        value_name = "allocation-sizes",
+        value_delimiter = ',',
+        help = "Comma separated sizes in bytes allocated in each iteration, e.g. `16,200,4096`",
+        value_hint = ValueHint::Other
+    )]
+    pub allocation_sizes: Vec<u32>,
+
+    #[arg(
+        long,
+        value_name = "free-every",
+        help = "Free the blocks of the previous iteration in every n-th iteration, after allocating the new ones",
+        value_hint = ValueHint::Other
+    )]
+    pub free_every: Option<u32>,
+
+    #[arg(
+        long,
+        value_name = "value-size",
+        help = "The size in bytes of each written storage value",
+        value_hint = ValueHint::Other
+    )]
+    pub value_size: Option<u32>,
+
+    #[arg(
+        long,
+        value_name = "storage-key",
+        help = "The hex-encoded storage key to be read (default: a random 32-byte key absent from any proof)",
+        value_hint = ValueHint::Other
+    )]
+    pub storage_key: Option<String>,
+
+    #[arg(
+        long,
+        value_name = "message-size",
+        help = "The size in bytes of each logged message",
+        value_hint = ValueHint::Other
+    )]
+    pub message_size: Option<u32>,
+
+    #[arg(
+        long,
+        value_enum,
+        value_name = "log-level",
+        help = "The level of the logged messages",
+        value_hint = ValueHint::Other
+    )]
+    pub log_level: Option<LogLevel>,
+
+    #[arg(
+        long,
+        value_enum,
+        value_name = "host-cpu-burn",
+        help = "The expensive host function to be called repeatedly",
+        value_hint = ValueHint::Other
+    )]
+    pub host_cpu_burn: Option<HostCpuBurn>,
+
+    #[arg(
+        long,
+        value_name = "buffer-size",
+        help = "The size in bytes of the buffer passed to the host function",
+        value_hint = ValueHint::Other
+    )]
+    pub buffer_size: Option<u32>,
+
+    #[arg(
+        long,
+        value_enum,
+        value_name = "forbidden-host-function",
+        help = "The host function disallowed in the validation context to be called",
+        value_hint = ValueHint::Other
+    )]
+    pub forbidden_host_function: Option<ForbiddenHostFunction>,
+
+    #[arg(
+        long,
+        value_name = "panic-message",
+        help = "The message the runtime panics with",
+        value_hint = ValueHint::Other
+    )]
+    pub panic_message: Option<String>,
+
+    #[arg(
+        long,
+        value_enum,
+        value_name = "stack-pointer-corruption",
+        help = "The value the stack pointer is set to",
+        value_hint = ValueHint::Other
+    )]
+    pub stack_pointer_corruption: Option<StackPointerCorruption>,
+
+    #[arg(
+        long,
+        value_name = "address",
+        help = "The address of the memory access, e.g. the size of the memory for one byte past the last page",
+        value_hint = ValueHint::Other
+    )]
+    pub address: Option<u32>,
+
+    #[arg(
+        long,
+        value_name = "offset",
+        help = "The static offset of the memory access, added to the address without wrapping [default: 0]",
+        value_hint = ValueHint::Other
+    )]
+    pub offset: Option<u32>,
+
+    #[arg(
+        long,
+        value_enum,
+        value_name = "width",
+        help = "The width in bits of the memory access",
+        value_hint = ValueHint::Other
+    )]
+    pub width: Option<AccessWidth>,
+
+    #[arg(
+        long,
+        value_enum,
+        value_name = "access",
+        help = "The direction of the memory access",
+        value_hint = ValueHint::Other
+    )]
+    pub access: Option<MemoryAccess>,
+
+    #[arg(
+        long,
+        value_name = "alignment",
+        help = "The log2 alignment hint of the memory access, at most the natural alignment [default: natural alignment]",
+        value_hint = ValueHint::Other
+    )]
+    pub alignment: Option<u32>,
+}
+
+/// # Arithmetic trap enum
+///
+/// Selects which arithmetic instruction the `ArithmeticTrap` injection uses to trap.
+#[derive(clap::ValueEnum, PartialEq, Eq, Clone, Copy, Debug)]
+pub enum ArithmeticTrap {
+    /// `i32.div_s` by zero
+    I32DivByZero,
+    /// `i64.div_s` of `INT_MIN` by -1
+    I64DivOverflow,
+    /// `i32.rem_u` by zero
+    I32RemByZero,
+    /// `i32.trunc_f32_s` of NaN
+    I32TruncNan,
+    /// `i32.trunc_f32_s` of a float that doesn't fit in an i32
+    I32TruncOverflow,
+}
+
+impl ArithmeticTrap {
+    /// # Returns a self-contained instruction sequence which traps with the selected trap.
+    fn instructions(self) -> Vec<Instruction> {
+        let trapping_instructions = match self {
+            ArithmeticTrap::I32DivByZero => vec![
+                Instruction::I32Const(1),
+                Instruction::I32Const(0),
+                Instruction::I32DivS,
+            ],
+            ArithmeticTrap::I64DivOverflow => vec![
+                Instruction::I64Const(i64::MIN),
+                Instruction::I64Const(-1),
+                Instruction::I64DivS,
+            ],
+            ArithmeticTrap::I32RemByZero => vec![
+                Instruction::I32Const(1),
+                Instruction::I32Const(0),
+                Instruction::I32RemU,
+            ],
+            ArithmeticTrap::I32TruncNan => vec![
+                Instruction::F32Const(f32::NAN.to_bits()),
+                Instruction::I32TruncSF32,
+            ],
+            ArithmeticTrap::I32TruncOverflow => vec![
+                Instruction::F32Const(3_000_000_000_f32.to_bits()),
+                Instruction::I32TruncSF32,
+            ],
+        };
+
+        // Drop the result to keep the stack balanced in case the host doesn't trap
+        [trapping_instructions, vec![Instruction::Drop]].concat()
+    }
+}
+
+/// # Indirect call fault enum
+///
+/// Selects what goes wrong in the `call_indirect` of the `IndirectCallFault` injection.
+#[derive(clap::ValueEnum, PartialEq, Eq, Clone, Copy, Debug)]
+pub enum IndirectCallFault {
+    /// Table index `u32::MAX`, past the size of any table
+    TableIndexOutOfBounds,
+    /// Type index which doesn't match the signature of the table element
+    SignatureMismatch,
+    /// Table slot which is not initialized by any element segment
+    NullElement,
+}
+
+/// # Pointer length fault enum
+///
+/// Selects what is wrong with the packed `(len << 32) | ptr` returned by the `BadPointerLength` injection.
+#[derive(clap::ValueEnum, PartialEq, Eq, Clone, Copy, Debug)]
+pub enum PointerLengthFault {
+    /// Pointer right past the end of the memory, or `u32::MAX` if the memory spans the whole 4 GiB
+    PointerBeyondMemory,
+    /// Valid pointer with a length of zero
+    ZeroLength,
+    /// Valid pointer with a length which overflows `ptr + len`
+    LengthOverflow,
+    /// Buffer right below the stack pointer, in the stack region
+    PointerIntoStack,
+    /// Valid in-bounds buffer filled with bytes which are not SCALE-decodable
+    NonScaleGarbage,
+}
+
+/// # Validation result fault enum
+///
+/// Selects what is semantically wrong with the well-formed `ValidationResult` returned by the `BadValidationResult` injection.
+#[derive(clap::ValueEnum, PartialEq, Eq, Clone, Copy, Debug)]
+pub enum ValidationResultFault {
+    /// `head_data` which doesn't match the candidate
+    WrongHeadData,
+    /// `new_validation_code` larger than the maximum code size
+    OversizedValidationCode,
+    /// `upward_messages` at the hard ceiling of 16384 messages by default, more than any configuration below
+    /// that ceiling allows. The count can't exceed the ceiling, as `upward_messages` wouldn't decode anymore.
+    TooManyUpwardMessages,
+    /// `horizontal_messages` which are not sorted by recipient
+    HrmpMessagesOutOfOrder,
+    /// `processed_downward_messages` larger than the number of available messages
+    InvalidProcessedDownwardMessages,
+}
+
+/// Hard ceiling of the number of upward messages, as defined by the Polkadot primitives.
+/// `upward_messages` is a vector bounded by it, so a larger count fails to decode.
+const MAX_UPWARD_MESSAGE_NUM: u32 = 16 * 1024;
+
+impl ValidationResultFault {
+    /// # Returns the SCALE-encoded `ValidationResult` with the selected fault.
+    /// # Fields unrelated to the fault are empty or zero.
+    /// # The upward message count is only used by the `TooManyUpwardMessages` fault.
+    fn encoded_validation_result(self, upward_message_count: u32) -> Vec<EncodedChunk> {
+        // Upper bound of the host configuration, as defined by the Polkadot primitives
+        const MAX_CODE_SIZE: u32 = 3 * 1024 * 1024;
+
+        let mut head_data = vec![EncodedChunk::Bytes(compact_encode(0))];
+        let mut new_validation_code = vec![EncodedChunk::Bytes(vec![0])];
+        let mut upward_messages = vec![EncodedChunk::Bytes(compact_encode(0))];
+        let mut horizontal_messages = vec![EncodedChunk::Bytes(compact_encode(0))];
+        let mut processed_downward_messages = 0_u32;
+        let hrmp_watermark = 0_u32;
+
+        match self {
+            ValidationResultFault::WrongHeadData => {
+                head_data = vec![
+                    EncodedChunk::Bytes(compact_encode(32)),
+                    EncodedChunk::Repeated(0xFF, 32),
+                ];
+            }
+            ValidationResultFault::OversizedValidationCode => {
+                new_validation_code = vec![
+                    EncodedChunk::Bytes([vec![1], compact_encode(MAX_CODE_SIZE + 1)].concat()),
+                    EncodedChunk::Repeated(0, MAX_CODE_SIZE + 1),
+                ];
+            }
+            ValidationResultFault::TooManyUpwardMessages => {
+                // Each message is empty, i.e. a single zero compact length
+                upward_messages = vec![
+                    EncodedChunk::Bytes(compact_encode(upward_message_count)),
+                    EncodedChunk::Repeated(0, upward_message_count),
+                ];
+            }
+            ValidationResultFault::HrmpMessagesOutOfOrder => {
+                horizontal_messages = vec![EncodedChunk::Bytes(
+                    [
+                        compact_encode(2),
+                        2001_u32.to_le_bytes().to_vec(),
+                        compact_encode(0),
+                        2000_u32.to_le_bytes().to_vec(),
+                        compact_encode(0),
+                    ]
+                    .concat(),
+                )];
+            }
+            ValidationResultFault::InvalidProcessedDownwardMessages => {
+                processed_downward_messages = u32::MAX;
+            }
+        }
+
+        [
+            head_data,
+            new_validation_code,
+            upward_messages,
+            horizontal_messages,
+            vec![EncodedChunk::Bytes(
+                [
+                    processed_downward_messages.to_le_bytes(),
+                    hrmp_watermark.to_le_bytes(),
+                ]
+                .concat(),
+            )],
+        ]
+        .concat()
+    }
+}
+
+/// # Host function misuse enum
+///
+/// Selects the invalid memory spans the `HostFunctionMisuse` injection passes to the host function.
+#[derive(clap::ValueEnum, PartialEq, Eq, Clone, Copy, Debug)]
+pub enum HostFunctionMisuse {
+    /// Pointers right past the end of the memory, or `u32::MAX` if the memory spans the whole 4 GiB
+    PointerBeyondMemory,
+    /// Lengths which wrap around u32 when added to their pointers
+    WrappingLength,
+    /// Every pointer points to the same buffer, so that outputs overlap inputs
+    OverlappingBuffers,
+}
+
+/// # Allocator misuse enum
+///
+/// Selects the invalid sequence of allocator calls issued by the `AllocatorMisuse` injection.
+#[derive(clap::ValueEnum, PartialEq, Eq, Clone, Copy, Debug)]
+pub enum AllocatorMisuse {
+    /// Allocate, free and free again
+    DoubleFree,
+    /// Allocate, free and write through the stale pointer
+    UseAfterFree,
+    /// Free a pointer which was never returned by the allocator
+    InvalidFree,
+}
+
+/// # Header corruption enum
+///
+/// Selects the pattern the `AllocatorHeaderCorruption` injection writes over the 8-byte allocation header.
+/// In the freeing-bump allocator, the header of an occupied allocation holds its order (size class)
+/// with bit 32 set, while the header of a free allocation holds a link to the next free allocation.
+#[derive(clap::ValueEnum, PartialEq, Eq, Clone, Copy, Debug)]
+pub enum HeaderCorruption {
+    /// Occupied header with an order past the largest size class
+    BadOrder,
+    /// Occupied header with a valid order which doesn't match the size of the allocation
+    BadSizeClass,
+    /// Free header linking to the allocation itself, as if it was in the free list
+    FreeListPointer,
+}
+
+/// # Level of a message logged through `ext_logging_log_version_1`
+/// The values match the `RuntimeInterfaceLogLevel` passed to the host.
+#[derive(clap::ValueEnum, PartialEq, Eq, Clone, Copy, Debug)]
+pub enum LogLevel {
+    /// Level 0
+    Error = 0,
+    /// Level 1
+    Warn = 1,
+    /// Level 2
+    Info = 2,
+    /// Level 3
+    Debug = 3,
+    /// Level 4
+    Trace = 4,
+}
+
+/// # Expensive host function, called over a large buffer
+#[derive(clap::ValueEnum, PartialEq, Eq, Clone, Copy, Debug)]
+pub enum HostCpuBurn {
+    /// `ext_hashing_blake2_256_version_1` hashing the buffer
+    Hashing,
+    /// `ext_crypto_sr25519_verify_version_2` verifying a signature of the buffer
+    Sr25519Verify,
+    /// `ext_trie_blake2_256_root_version_1` computing the root of a trie holding the buffer
+    TrieRoot,
+}
+
+impl HostCpuBurn {
+    /// # Returns the name and the type of the host function.
+    fn host_function(self) -> (&'static str, FunctionType) {
+        match self {
+            HostCpuBurn::Hashing => (
+                "ext_hashing_blake2_256_version_1",
+                FunctionType::new(vec![ValueType::I64], vec![ValueType::I32]),
+            ),
+            HostCpuBurn::Sr25519Verify => (
+                "ext_crypto_sr25519_verify_version_2",
+                FunctionType::new(
+                    vec![ValueType::I32, ValueType::I64, ValueType::I32],
+                    vec![ValueType::I32],
+                ),
+            ),
+            HostCpuBurn::TrieRoot => (
+                "ext_trie_blake2_256_root_version_1",
+                FunctionType::new(vec![ValueType::I64], vec![ValueType::I32]),
+            ),
+        }
+    }
+}
+
+/// Well-formed sr25519 signature, a zero `R` and a zero scalar with the schnorrkel marker bit set in the last byte.
+/// It doesn't match any message, but passes the checks done before the message is hashed.
+const SR25519_SIGNATURE: [u8; 64] = {
+    let mut signature = [0; 64];
+    signature[63] = 0x80;
+    signature
+};
+
+/// Canonical encoding of the Ristretto basepoint, a valid sr25519 public key.
+const SR25519_PUBLIC_KEY: [u8; 32] = [
+    0xe2, 0xf2, 0xae, 0x0a, 0x6a, 0xbc, 0x4e, 0x71, 0xa8, 0x84, 0xa9, 0x61, 0xc5, 0x00, 0x51, 0x5f,
+    0x58, 0xe3, 0x0b, 0x6a, 0xa5, 0x82, 0xdd, 0x8d, 0xb6, 0xa6, 0x59, 0x45, 0xe0, 0x8d, 0x2d, 0x76,
+];
+
+/// # Non-deterministic host function, which must not be available when validating a candidate
+#[derive(clap::ValueEnum, PartialEq, Eq, Clone, Copy, Debug)]
+pub enum ForbiddenHostFunction {
+    /// `ext_offchain_timestamp_version_1`
+    Timestamp,
+    /// `ext_offchain_http_request_start_version_1` with a GET request to localhost
+    HttpRequestStart,
+    /// `ext_offchain_random_seed_version_1`
+    RandomSeed,
+}
+
+impl ForbiddenHostFunction {
+    /// # Returns the name and the type of the host function.
+    fn host_function(self) -> (&'static str, FunctionType) {
+        match self {
+            ForbiddenHostFunction::Timestamp => (
+                "ext_offchain_timestamp_version_1",
+                FunctionType::new(vec![], vec![ValueType::I64]),
+            ),
+            ForbiddenHostFunction::HttpRequestStart => (
+                "ext_offchain_http_request_start_version_1",
+                FunctionType::new(vec![ValueType::I64; 3], vec![ValueType::I64]),
+            ),
+            ForbiddenHostFunction::RandomSeed => (
+                "ext_offchain_random_seed_version_1",
+                FunctionType::new(vec![], vec![ValueType::I32]),
+            ),
+        }
+    }
+}
+
+/// # Stack pointer corruption enum
+///
+/// Selects the value `__stack_pointer` is set to by the `StackPointerCorruption` injection.
+/// The shadow stack grows down from `__stack_pointer`, towards the start of the memory.
+#[derive(clap::ValueEnum, PartialEq, Eq, Clone, Copy, Debug)]
+pub enum StackPointerCorruption {
+    /// Zero, so that the stack immediately grows past the start of the memory
+    Zero,
+    /// 16-byte aligned value right below `__data_end`, so that the stack overwrites the static data
+    BelowDataEnd,
+    /// Last 16-byte aligned address, so that the stack frames are past the end of any memory smaller than 4 GiB
+    PastMemory,
+}
+
+/// # Width of a memory access
+#[derive(clap::ValueEnum, PartialEq, Eq, Clone, Copy, Debug)]
+pub enum AccessWidth {
+    /// 8 bits
+    #[value(name = "8")]
+    Bits8,
+    /// 16 bits
+    #[value(name = "16")]
+    Bits16,
+    /// 32 bits
+    #[value(name = "32")]
+    Bits32,
+    /// 64 bits
+    #[value(name = "64")]
+    Bits64,
+}
+
+impl AccessWidth {
+    /// # Returns the log2 of the width in bytes, i.e. the natural alignment of the access.
+    fn natural_alignment(self) -> u32 {
+        match self {
+            AccessWidth::Bits8 => 0,
+            AccessWidth::Bits16 => 1,
+            AccessWidth::Bits32 => 2,
+            AccessWidth::Bits64 => 3,
+        }
+    }
+}
+
+/// # Direction of a memory access
+#[derive(clap::ValueEnum, PartialEq, Eq, Clone, Copy, Debug)]
+pub enum MemoryAccess {
+    /// Load, whose result is dropped
+    Load,
+    /// Store of a constant
+    Store,
 }
 
 impl Injection {
@@ -38,14 +666,88 @@ impl Injection {
         self,
         module: &mut Module,
         function: &str,
-        size: Option<i16>,
+        options: &InjectionOptions,
     ) -> Result<(), String> {
         match self {
             Injection::InfiniteLoop => inject_infinite_loop(module, function),
             Injection::BadReturnValue => inject_bad_return_value(module, function),
             Injection::StackOverflow => inject_stack_overflow(module, function),
-            Injection::Noops => inject_noops(module, function, size),
+            Injection::Noops => inject_noops(module, function, options.size),
             Injection::HeapOverflow => inject_heap_overflow(module, function),
+            Injection::Unreachable => inject_unreachable(module, function),
+            Injection::ArithmeticTrap => inject_arithmetic_trap(module, function, options.trap),
+            Injection::StackExhaustion => inject_stack_exhaustion(module, function, options.locals),
+            Injection::MemoryGrowExhaustion => inject_memory_grow_exhaustion(module, function),
+            Injection::IndirectCallFault => {
+                inject_indirect_call_fault(module, function, options.indirect_call_fault)
+            }
+            Injection::BoundedLoop => inject_bounded_loop(module, function, options.iterations),
+            Injection::BadPointerLength => {
+                inject_bad_pointer_length(module, function, options.pointer_length_fault)
+            }
+            Injection::BadValidationResult => inject_bad_validation_result(
+                module,
+                function,
+                options.validation_result_fault,
+                options.upward_messages,
+            ),
+            Injection::HostFunctionMisuse => inject_host_function_misuse(
+                module,
+                function,
+                options.host_function.as_deref(),
+                options.host_function_misuse,
+            ),
+            Injection::AllocatorMisuse => {
+                inject_allocator_misuse(module, function, options.allocator_misuse)
+            }
+            Injection::AllocatorHeaderCorruption => {
+                inject_allocator_header_corruption(module, function, options.header_corruption)
+            }
+            Injection::AllocatorFragmentation => inject_allocator_fragmentation(
+                module,
+                function,
+                &options.allocation_sizes,
+                options.iterations,
+                options.free_every,
+            ),
+            Injection::StorageWriteSpam => {
+                inject_storage_write_spam(module, function, options.iterations, options.value_size)
+            }
+            Injection::StorageProofMiss => {
+                inject_storage_proof_miss(module, function, options.storage_key.as_deref())
+            }
+            Injection::LoggingSpam => inject_logging_spam(
+                module,
+                function,
+                options.iterations,
+                options.message_size,
+                options.log_level,
+            ),
+            Injection::HostCpuBurn => inject_host_cpu_burn(
+                module,
+                function,
+                options.host_cpu_burn,
+                options.iterations,
+                options.buffer_size,
+            ),
+            Injection::ForbiddenHostCall => {
+                inject_forbidden_host_call(module, function, options.forbidden_host_function)
+            }
+            Injection::RuntimePanic => {
+                inject_runtime_panic(module, function, options.panic_message.as_deref())
+            }
+            Injection::StackPointerCorruption => {
+                inject_stack_pointer_corruption(module, function, options.stack_pointer_corruption)
+            }
+            Injection::OutOfBoundsAccess => inject_out_of_bounds_access(
+                module,
+                function,
+                options.address,
+                options.offset,
+                options.width,
+                options.access,
+                options.alignment,
+            ),
         }
     }
 }
@@ -58,6 +760,26 @@ impl Display for Injection {
             Injection::StackOverflow => write!(f, "stack-overflow"),
             Injection::Noops => write!(f, "noops"),
             Injection::HeapOverflow => write!(f, "heap-overflow"),
+            Injection::Unreachable => write!(f, "unreachable"),
+            Injection::ArithmeticTrap => write!(f, "arithmetic-trap"),
+            Injection::StackExhaustion => write!(f, "stack-exhaustion"),
+            Injection::MemoryGrowExhaustion => write!(f, "memory-grow-exhaustion"),
+            Injection::IndirectCallFault => write!(f, "indirect-call-fault"),
+            Injection::BoundedLoop => write!(f, "bounded-loop"),
+            Injection::BadPointerLength => write!(f, "bad-pointer-length"),
+            Injection::BadValidationResult => write!(f, "bad-validation-result"),
+            Injection::HostFunctionMisuse => write!(f, "host-function-misuse"),
+            Injection::AllocatorMisuse => write!(f, "allocator-misuse"),
+            Injection::AllocatorHeaderCorruption => write!(f, "allocator-header-corruption"),
+            Injection::AllocatorFragmentation => write!(f, "allocator-fragmentation"),
+            Injection::StorageWriteSpam => write!(f, "storage-write-spam"),
+            Injection::StorageProofMiss => write!(f, "storage-proof-miss"),
+            Injection::LoggingSpam => write!(f, "logging-spam"),
+            Injection::HostCpuBurn => write!(f, "host-cpu-burn"),
+            Injection::ForbiddenHostCall => write!(f, "forbidden-host-call"),
+            Injection::RuntimePanic => write!(f, "runtime-panic"),
+            Injection::StackPointerCorruption => write!(f, "stack-pointer-corruption"),
+            Injection::OutOfBoundsAccess => write!(f, "out-of-bounds-access"),
         }
     }
 }
@@ -80,14 +802,39 @@ fn inject_infinite_loop(module: &mut Module, function_name: &str) -> Result<(),
     })
 }
 
-/// # Takes a module and injects a store and  return on the last value from the stack in the beginning of the module.
+/// # Takes a module and replaces the function body with garbage return values.
+/// # A garbage value of the matching type is returned for each result of the function.
+///
+/// # Errors
+/// - Returns an error if the function doesn't return anything.
 fn inject_bad_return_value(module: &mut Module, function_name: &str) -> Result<(), String> {
+    let global_function_index = module.get_global_function_index(function_name)?;
+    let results = module
+        .get_function_type(global_function_index)?
+        .results()
+        .to_vec();
+
+    if results.is_empty() {
+        return Err(format!(
+            "Function '{}' has no return value to be replaced",
+            function_name
+        ));
+    }
+
     module.map_function(function_name, |func_body: &mut FuncBody| {
-        *func_body.code_mut() = Instructions::new(vec![
-            // Last value on the stack gets returned
-            Instruction::I64Const(123456789),
-            Instruction::End,
-        ]);
+        let bad_return_values = results.iter().map(|result| match result {
+            ValueType::I32 => Instruction::I32Const(123456789),
+            ValueType::I64 => Instruction::I64Const(123456789),
+            ValueType::F32 => Instruction::F32Const(123456789_f32.to_bits()),
+            ValueType::F64 => Instruction::F64Const(123456789_f64.to_bits()),
+        });
+
+        *func_body.code_mut() = Instructions::new(
+            bad_return_values
+                // Last values on the stack get returned
+                .chain([Instruction::End])
+                .collect(),
+        );
     })
 }
 
@@ -153,112 +900,3172 @@ fn inject_heap_overflow(module: &mut Module, function_name: &str) -> Result<(),
     })
 }
 
-#[cfg(test)]
-mod injections_tests {
-    use super::*;
-    use crate::util::load_module_from_wasm;
-    use std::path::Path;
+/// # Takes a module and injects an invalid sequence of allocator calls in the beginning of the module.
+/// # This injection relies on the existence of the `ext_allocator_malloc` and `ext_allocator_free` functions as per
+/// # Polkadot's specification https://spec.polkadot.network/chap-host-api#sect-allocator-api
+fn inject_allocator_misuse(
+    module: &mut Module,
+    function_name: &str,
+    misuse: Option<AllocatorMisuse>,
+) -> Result<(), String> {
+    let misuse = misuse.ok_or("No allocator misuse given")?;
 
-    const FUNCTION_NAME: &'static str = "validate_block";
-    const WASM_PATH: &'static str = concat!(env!("CARGO_MANIFEST_DIR"), "/samples/example.wasm");
+    let malloc_index = module.get_malloc_index()? as u32;
+    let free_index = module.get_free_index()? as u32;
+    let pointer_local = module.get_next_local_index(function_name)? as u32;
 
-    fn get_function_body(module: &mut Module) -> &mut FuncBody {
-        let function_name = "validate_block";
-        let global_function_index = module.get_global_function_index(function_name).unwrap();
-        let import_section_len = module.get_import_section_len().unwrap();
-        let local_function_index = global_function_index - import_section_len;
-        let function_body = module
-            .get_function_body(local_function_index, function_name)
-            .unwrap();
+    module.map_function(function_name, |func_body: &mut FuncBody| {
+        func_body.locals_mut().push(Local::new(1, ValueType::I32));
+        let code = func_body.code_mut();
 
-        function_body
-    }
+        let allocation = vec![
+            Instruction::I32Const(64),
+            Instruction::Call(malloc_index),
+            Instruction::SetLocal(pointer_local),
+        ];
+        let misuse = match misuse {
+            AllocatorMisuse::DoubleFree => vec![
+                Instruction::GetLocal(pointer_local),
+                Instruction::Call(free_index),
+                Instruction::GetLocal(pointer_local),
+                Instruction::Call(free_index),
+            ],
+            AllocatorMisuse::UseAfterFree => vec![
+                Instruction::GetLocal(pointer_local),
+                Instruction::Call(free_index),
+                Instruction::GetLocal(pointer_local),
+                Instruction::I64Const(-1),
+                Instruction::I64Store(3, 0),
+            ],
+            // A pointer into the middle of an allocation was never returned by malloc
+            AllocatorMisuse::InvalidFree => vec![
+                Instruction::GetLocal(pointer_local),
+                Instruction::I32Const(32),
+                Instruction::I32Add,
+                Instruction::Call(free_index),
+            ],
+        };
 
-    fn load_module() -> Module {
-        let module_path = Path::new(WASM_PATH);
-        let module = load_module_from_wasm(module_path).unwrap();
-        module
+        let mut code_with_misuse = [allocation, misuse].concat();
+        code_with_misuse.append(code.elements_mut());
+
+        *code.elements_mut() = code_with_misuse;
+    })
+}
+
+/// # Takes a module and injects an allocation, whose header gets corrupted before it is freed, in the beginning of the module.
+/// # This injection relies on the existence of the `ext_allocator_malloc` and `ext_allocator_free` functions as per
+/// # Polkadot's specification https://spec.polkadot.network/chap-host-api#sect-allocator-api
+fn inject_allocator_header_corruption(
+    module: &mut Module,
+    function_name: &str,
+    corruption: Option<HeaderCorruption>,
+) -> Result<(), String> {
+    let corruption = corruption.ok_or("No header corruption given")?;
+
+    let malloc_index = module.get_malloc_index()? as u32;
+    let free_index = module.get_free_index()? as u32;
+    let pointer_local = module.get_next_local_index(function_name)? as u32;
+
+    // Bit 32 marks the header of an occupied allocation
+    const OCCUPIED: i64 = 1 << 32;
+    // There are 23 orders, from 8 bytes to 32 MiB
+    const MAX_ORDER: i64 = 22;
+
+    module.map_function(function_name, |func_body: &mut FuncBody| {
+        func_body.locals_mut().push(Local::new(1, ValueType::I32));
+        let code = func_body.code_mut();
+
+        let header = match corruption {
+            HeaderCorruption::BadOrder => vec![Instruction::I64Const(OCCUPIED | (MAX_ORDER + 1))],
+            // The allocation of 64 bytes has an order of 3
+            HeaderCorruption::BadSizeClass => vec![Instruction::I64Const(OCCUPIED | MAX_ORDER)],
+            HeaderCorruption::FreeListPointer => vec![
+                Instruction::GetLocal(pointer_local),
+                Instruction::I32Const(8),
+                Instruction::I32Sub,
+                Instruction::I64ExtendUI32,
+            ],
+        };
+
+        let mut code_with_corruption = [
+            vec![
+                Instruction::I32Const(64),
+                Instruction::Call(malloc_index),
+                Instruction::SetLocal(pointer_local),
+                // The header is located right before the pointer
+                Instruction::GetLocal(pointer_local),
+                Instruction::I32Const(8),
+                Instruction::I32Sub,
+            ],
+            header,
+            vec![
+                Instruction::I64Store(3, 0),
+                Instruction::GetLocal(pointer_local),
+                Instruction::Call(free_index),
+            ],
+        ]
+        .concat();
+        code_with_corruption.append(code.elements_mut());
+
+        *code.elements_mut() = code_with_corruption;
+    })
+}
+
+/// # Takes a module and injects a loop of mixed-size allocations in the beginning of the module.
+/// # Each iteration allocates one block of every size. Optionally, every n-th iteration frees the blocks of the
+/// # previous iteration, kept in one local per size, after the newer blocks are allocated. The holes left between
+/// # live blocks only fit allocations of their own size, so that the free lists of the allocator fragment.
+/// # This injection relies on the existence of the `ext_allocator_malloc` and `ext_allocator_free` functions as per
+/// # Polkadot's specification https://spec.polkadot.network/chap-host-api#sect-allocator-api
+fn inject_allocator_fragmentation(
+    module: &mut Module,
+    function_name: &str,
+    allocation_sizes: &[u32],
+    iterations: Option<u32>,
+    free_every: Option<u32>,
+) -> Result<(), String> {
+    if allocation_sizes.is_empty() {
+        return Err("No allocation sizes given".to_string());
+    }
+    let iterations = iterations.ok_or("No iterations given")?;
+    if free_every == Some(0) {
+        return Err("Blocks can't be freed every 0 iterations".to_string());
     }
 
-    #[test]
-    fn test_inject_infinite_loop() {
-        let mut module = load_module();
+    let malloc_index = module.get_malloc_index()? as u32;
+    let free_index = match free_every {
+        Some(_) => Some(module.get_free_index()? as u32),
+        None => None,
+    };
+    let counter_local = module.get_next_local_index(function_name)? as u32;
+    let pointer_local = counter_local + 1;
+    // Locals holding the block of every size allocated by the previous iteration
+    let previous_local = |index: usize| pointer_local + 1 + index as u32;
 
-        let injection = Injection::InfiniteLoop;
-        assert!(injection.inject(&mut module, FUNCTION_NAME, None).is_ok());
+    let allocations: Vec<Instruction> = allocation_sizes
+        .iter()
+        .enumerate()
+        .flat_map(|(index, size)| {
+            let allocation = [
+                Instruction::I32Const(*size as i32),
+                Instruction::Call(malloc_index),
+            ];
 
-        let function_body = get_function_body(&mut module);
+            match (free_every, free_index) {
+                (Some(free_every), Some(free_index)) => [
+                    allocation.to_vec(),
+                    vec![
+                        Instruction::SetLocal(pointer_local),
+                        // Free the previous block of this size in every n-th iteration,
+                        // unless there is none yet
+                        Instruction::GetLocal(counter_local),
+                        Instruction::I32Const(free_every as i32),
+                        Instruction::I32RemU,
+                        Instruction::I32Eqz,
+                        Instruction::GetLocal(previous_local(index)),
+                        Instruction::I32Const(0),
+                        Instruction::I32Ne,
+                        Instruction::I32And,
+                        Instruction::If(BlockType::NoResult),
+                        Instruction::GetLocal(previous_local(index)),
+                        Instruction::Call(free_index),
+                        Instruction::End,
+                        Instruction::GetLocal(pointer_local),
+                        Instruction::SetLocal(previous_local(index)),
+                    ],
+                ]
+                .concat(),
+                _ => [allocation.to_vec(), vec![Instruction::Drop]].concat(),
+            }
+        })
+        .collect();
+    let locals_count = match free_every {
+        Some(_) => 2 + allocation_sizes.len() as u32,
+        None => 2,
+    };
 
-        let expected = vec![
+    module.map_function(function_name, |func_body: &mut FuncBody| {
+        func_body
+            .locals_mut()
+            .push(Local::new(locals_count, ValueType::I32));
+        let code = func_body.code_mut();
+
+        let mut code_with_allocations =
+            bounded_loop(counter_local, iterations, allocations.clone());
+        code_with_allocations.append(code.elements_mut());
+
+        *code.elements_mut() = code_with_allocations;
+    })
+}
+
+/// # Takes a module and injects an explicit trap in the beginning of the module.
+fn inject_unreachable(module: &mut Module, function_name: &str) -> Result<(), String> {
+    module.map_function(function_name, |func_body: &mut FuncBody| {
+        let code = func_body.code_mut();
+
+        let mut code_with_trap = vec![
+            // Traps unconditionally
+            Instruction::Unreachable,
+        ];
+        code_with_trap.append(code.elements_mut());
+
+        *code.elements_mut() = code_with_trap;
+    })
+}
+
+/// # Takes a module and injects an arithmetic instruction which traps in the beginning of the module.
+fn inject_arithmetic_trap(
+    module: &mut Module,
+    function_name: &str,
+    trap: Option<ArithmeticTrap>,
+) -> Result<(), String> {
+    let trap = trap.ok_or("No arithmetic trap given")?;
+
+    module.map_function(function_name, |func_body: &mut FuncBody| {
+        let code = func_body.code_mut();
+
+        let mut code_with_trap = trap.instructions();
+        code_with_trap.append(code.elements_mut());
+
+        *code.elements_mut() = code_with_trap;
+    })
+}
+
+/// # Takes a module, appends a self-recursive function to it and calls it in the beginning of the module.
+/// # The recursion never ends, so it exhausts the call stack of the host.
+/// # Optional additional locals inflate the size of each stack frame.
+fn inject_stack_exhaustion(
+    module: &mut Module,
+    function_name: &str,
+    locals: Option<u32>,
+) -> Result<(), String> {
+    let locals = locals.unwrap_or(0);
+
+    // Append the recursive function `(i64) -> i64` ...
+    let recursive_function_index = module.add_function(
+        FunctionType::new(vec![ValueType::I64], vec![ValueType::I64]),
+        FuncBody::new(
+            match locals {
+                0 => vec![],
+                count => vec![Local::new(count, ValueType::I64)],
+            },
+            Instructions::empty(),
+        ),
+    )?;
+    let local_function_index = recursive_function_index - module.get_import_section_len()?;
+
+    // ... and make it call itself. The additional locals are set before and used after
+    // the recursive call, so that they stay alive (and on the stack) during the call.
+    let code = (1..=locals)
+        .flat_map(|local| {
+            [
+                Instruction::GetLocal(0),
+                Instruction::I64Const(local as i64),
+                Instruction::I64Add,
+                Instruction::SetLocal(local),
+            ]
+        })
+        .chain([
+            Instruction::GetLocal(0),
+            Instruction::I64Const(1),
+            Instruction::I64Add,
+            Instruction::Call(recursive_function_index as u32),
+        ])
+        .chain((1..=locals).flat_map(|local| [Instruction::GetLocal(local), Instruction::I64Add]))
+        .chain([Instruction::End])
+        .collect();
+    *module
+        .get_function_body(local_function_index, "stack_exhaustion")?
+        .code_mut() = Instructions::new(code);
+
+    module.map_function(function_name, |func_body: &mut FuncBody| {
+        let code = func_body.code_mut();
+
+        let mut code_with_recursion = vec![
+            // Never returns
+            Instruction::I64Const(0),
+            Instruction::Call(recursive_function_index as u32),
+            Instruction::Drop,
+        ];
+        code_with_recursion.append(code.elements_mut());
+
+        *code.elements_mut() = code_with_recursion;
+    })
+}
+
+/// # Takes a module and grows its memory one page at a time until `memory.grow` fails in the beginning of the module.
+/// # The growth is bounded by the declared maximum pages of the memory, or by the host's limit if there is none.
+/// # The last 8 bytes of the grown memory are written afterwards.
+fn inject_memory_grow_exhaustion(module: &mut Module, function_name: &str) -> Result<(), String> {
+    let has_memory = module
+        .memory_section()
+        .is_some_and(|memory_section| !memory_section.entries().is_empty())
+        || module.import_section().is_some_and(|import_section| {
+            import_section
+                .entries()
+                .iter()
+                .any(|entry| matches!(entry.external(), External::Memory(_)))
+        });
+    if !has_memory {
+        return Err("No memory in the module".to_string());
+    }
+
+    module.map_function(function_name, |func_body: &mut FuncBody| {
+        let code = func_body.code_mut();
+
+        let mut code_with_growth = vec![
+            // Grow by a single page until `memory.grow` returns -1
+            Instruction::Block(BlockType::NoResult),
             Instruction::Loop(BlockType::NoResult),
-            Instruction::Nop,
+            Instruction::I32Const(1),
+            Instruction::GrowMemory(0),
+            Instruction::I32Const(-1),
+            Instruction::I32Eq,
+            Instruction::BrIf(1),
             Instruction::Br(0),
             Instruction::End,
+            Instruction::End,
+            // Touch the last page: memory.size * 64KiB - 8
+            Instruction::CurrentMemory(0),
+            Instruction::I32Const(16),
+            Instruction::I32Shl,
+            Instruction::I32Const(8),
+            Instruction::I32Sub,
+            Instruction::I64Const(-1),
+            Instruction::I64Store(3, 0),
         ];
-        assert!(function_body.code_mut().elements().starts_with(&expected))
-    }
+        code_with_growth.append(code.elements_mut());
 
-    #[test]
-    fn test_inject_jibberish_return_value() {
-        let mut module = load_module();
-        let injection = Injection::BadReturnValue;
+        *code.elements_mut() = code_with_growth;
+    })
+}
 
-        assert!(injection.inject(&mut module, FUNCTION_NAME, None).is_ok());
+/// # Takes a module and injects a faulty indirect call in the beginning of the module.
+/// # The table and element sections are inspected, so that the selected fault is guaranteed.
+///
+/// # Errors
+/// - Returns an error if the module has no table.
+/// - Returns an error if the selected fault can't be guaranteed, e.g. a null element of an imported or growable table.
+fn inject_indirect_call_fault(
+    module: &mut Module,
+    function_name: &str,
+    fault: Option<IndirectCallFault>,
+) -> Result<(), String> {
+    let fault = fault.ok_or("No indirect call fault given")?;
 
-        let function_body = get_function_body(&mut module);
+    // Imported tables come first in the table index space
+    let imported_table = module.import_section().and_then(|import_section| {
+        import_section
+            .entries()
+            .iter()
+            .find_map(|entry| match entry.external() {
+                External::Table(table_type) => Some(*table_type.limits()),
+                _ => None,
+            })
+    });
+    let table_limits = imported_table
+        .or_else(|| {
+            module
+                .table_section()
+                .and_then(|table_section| table_section.entries().first())
+                .map(|table_type| *table_type.limits())
+        })
+        .ok_or("No table in the module")?;
 
-        let expected = vec![Instruction::I64Const(123456789), Instruction::End];
-        assert!(function_body.code_mut().elements().starts_with(&expected))
+    // Collect the function index of every initialized slot of the table
+    let mut table_elements = BTreeMap::new();
+    for segment in module
+        .elements_section()
+        .map(|elements_section| elements_section.entries())
+        .unwrap_or_default()
+        .iter()
+        .filter(|segment| segment.index() == 0)
+    {
+        let offset = match segment.offset().as_ref().map(InitExpr::code) {
+            Some([Instruction::I32Const(offset), Instruction::End]) => *offset as u32,
+            _ => return Err("Element segment offset is not a constant".to_string()),
+        };
+
+        for (position, function_index) in segment.members().iter().enumerate() {
+            table_elements.insert(offset + position as u32, *function_index);
+        }
     }
 
-    #[test]
-    fn test_inject_stack_overflow() {
-        let mut module = load_module();
+    let (table_index, call_type) = match fault {
+        // u32::MAX is past the end of any table, whatever size it is grown to
+        IndirectCallFault::TableIndexOutOfBounds => (u32::MAX, FunctionType::default()),
+        IndirectCallFault::SignatureMismatch => {
+            let (table_index, function_index) = table_elements
+                .iter()
+                .next()
+                .ok_or("No initialized table element")?;
+            let function_type = module.get_function_type(*function_index as usize)?;
 
-        let injection = Injection::StackOverflow;
-        assert!(injection.inject(&mut module, FUNCTION_NAME, None).is_ok());
+            // Call the element with any other signature
+            let call_type = if function_type == FunctionType::default() {
+                FunctionType::new(vec![], vec![ValueType::I32])
+            } else {
+                FunctionType::default()
+            };
 
-        let function_body = get_function_body(&mut module);
+            (*table_index, call_type)
+        }
+        IndirectCallFault::NullElement => {
+            // The slots past the initial size of the table could be filled after growing it
+            if imported_table.is_some() {
+                return Err("The table is imported, so its elements are unknown".to_string());
+            }
+            if table_limits.maximum() != Some(table_limits.initial()) {
+                return Err("The table can grow, so its elements are unknown".to_string());
+            }
 
-        let expected = vec![
-            Instruction::GetLocal(2),
-            Instruction::I32Const(2147483647),
-            Instruction::I32Store(2, 81000000),
+            let table_index = (0..table_limits.initial())
+                .find(|table_index| !table_elements.contains_key(table_index))
+                .ok_or("No uninitialized table element")?;
+
+            (table_index, FunctionType::default())
+        }
+    };
+    let results_count = call_type.results().len();
+    let type_index = module.get_or_add_type_index(call_type)?;
+
+    module.map_function(function_name, |func_body: &mut FuncBody| {
+        let code = func_body.code_mut();
+
+        let mut code_with_call = vec![
+            Instruction::I32Const(table_index as i32),
+            Instruction::CallIndirect(type_index as u32, 0),
         ];
-        assert!(function_body.code_mut().elements().starts_with(&expected))
-    }
+        // Keep the stack balanced in case the host doesn't trap
+        code_with_call.append(&mut vec![Instruction::Drop; results_count]);
+        code_with_call.append(code.elements_mut());
 
-    #[test]
-    fn test_inject_noops() {
-        let mut module = load_module();
+        *code.elements_mut() = code_with_call;
+    })
+}
 
-        let injection = Injection::Noops;
-        assert!(injection
-            .inject(&mut module, FUNCTION_NAME, Some(10))
-            .is_ok());
+/// # Takes a module and injects a loop with the given number of iterations in the beginning of the module.
+/// # The loop counter is kept in a fresh local and the original body runs after the loop.
+fn inject_bounded_loop(
+    module: &mut Module,
+    function_name: &str,
+    iterations: Option<u32>,
+) -> Result<(), String> {
+    let iterations = iterations.ok_or("No iterations given")?;
+    let counter = module.get_next_local_index(function_name)? as u32;
 
-        let function_body = get_function_body(&mut module);
+    module.map_function(function_name, |func_body: &mut FuncBody| {
+        func_body.locals_mut().push(Local::new(1, ValueType::I32));
+        let code = func_body.code_mut();
 
-        let expected = vec![Instruction::Nop; 50]; // 500_000_000 is the actual number of injected instructions
-        assert!(function_body.code_mut().elements().starts_with(&expected))
-    }
+        let mut code_with_loop = bounded_loop(counter, iterations, vec![Instruction::Nop]);
+        code_with_loop.append(code.elements_mut());
 
-    #[test]
-    fn test_inject_heap_overflow() {
-        let mut module = load_module();
+        *code.elements_mut() = code_with_loop;
+    })
+}
 
-        let injection = Injection::HeapOverflow;
-        assert!(injection.inject(&mut module, FUNCTION_NAME, None).is_ok());
+/// # Wraps the body in a loop which runs the given number of iterations.
+/// # The counter is an i32 local which should be zero before the loop, compared as unsigned.
+fn bounded_loop(counter: u32, iterations: u32, mut body: Vec<Instruction>) -> Vec<Instruction> {
+    let mut code = vec![
+        Instruction::Block(BlockType::NoResult),
+        Instruction::Loop(BlockType::NoResult),
+        // Break out of the block once the counter reaches the iterations
+        Instruction::GetLocal(counter),
+        Instruction::I32Const(iterations as i32),
+        Instruction::I32GeU,
+        Instruction::BrIf(1),
+    ];
+    code.append(&mut body);
+    code.append(&mut vec![
+        Instruction::GetLocal(counter),
+        Instruction::I32Const(1),
+        Instruction::I32Add,
+        Instruction::SetLocal(counter),
+        Instruction::Br(0),
+        Instruction::End,
+        Instruction::End,
+    ]);
 
-        let index = module.get_malloc_index().unwrap() as u32;
-        let function_body = get_function_body(&mut module);
+    code
+}
 
-        let expected = vec![Instruction::I32Const(33_554_431), Instruction::Call(index)]
-            .into_iter()
-            .cycle()
-            .take(16)
-            .collect::<Vec<_>>();
+/// # Takes a module and replaces the function body with a faulty packed `(len << 32) | ptr` return value,
+/// # as returned by Substrate runtime APIs.
+///
+/// # Errors
+/// - Returns an error if the function doesn't return a single i64.
+/// - Returns an error if the selected fault needs a global or function which is not in the module.
+fn inject_bad_pointer_length(
+    module: &mut Module,
+    function_name: &str,
+    fault: Option<PointerLengthFault>,
+) -> Result<(), String> {
+    let fault = fault.ok_or("No pointer length fault given")?;
 
-        assert!(function_body.code_mut().elements().starts_with(&expected))
+    ensure_returns_pointer_length(module, function_name)?;
+
+    const LENGTH: u32 = 32;
+    let pointer_local = module.get_next_local_index(function_name)? as u32;
+
+    let (locals, code) = match fault {
+        PointerLengthFault::PointerBeyondMemory => (
+            vec![],
+            [first_address_past_memory(), packed_pointer_length(LENGTH)].concat(),
+        ),
+        PointerLengthFault::ZeroLength => (
+            vec![],
+            [vec![Instruction::I32Const(8)], packed_pointer_length(0)].concat(),
+        ),
+        PointerLengthFault::LengthOverflow => (
+            vec![],
+            // The pointer is in bounds, so that only the length is faulty
+            [
+                vec![Instruction::I32Const(8)],
+                packed_pointer_length(u32::MAX),
+            ]
+            .concat(),
+        ),
+        PointerLengthFault::PointerIntoStack => {
+            let stack_pointer_index = module.get_global_index("__stack_pointer")? as u32;
+
+            (
+                vec![],
+                // The stack grows down from the stack pointer, whereas the memory above it holds the static data
+                [
+                    vec![
+                        Instruction::GetGlobal(stack_pointer_index),
+                        Instruction::I32Const(LENGTH as i32),
+                        Instruction::I32Sub,
+                    ],
+                    packed_pointer_length(LENGTH),
+                ]
+                .concat(),
+            )
+        }
+        PointerLengthFault::NonScaleGarbage => {
+            let malloc_index = module.get_malloc_index()? as u32;
+
+            let allocation = vec![
+                Instruction::I32Const(LENGTH as i32),
+                Instruction::Call(malloc_index),
+                Instruction::SetLocal(pointer_local),
+            ];
+            // 0xFF is neither a valid enum variant nor a valid compact length prefix
+            let garbage = (0..LENGTH / 8).flat_map(|word| {
+                [
+                    Instruction::GetLocal(pointer_local),
+                    Instruction::I64Const(-1),
+                    Instruction::I64Store(3, word * 8),
+                ]
+            });
+
+            (
+                vec![Local::new(1, ValueType::I32)],
+                allocation
+                    .into_iter()
+                    .chain(garbage)
+                    .chain([Instruction::GetLocal(pointer_local)])
+                    .chain(packed_pointer_length(LENGTH))
+                    .collect(),
+            )
+        }
+    };
+
+    module.map_function(function_name, |func_body: &mut FuncBody| {
+        func_body.locals_mut().append(&mut locals.clone());
+        *func_body.code_mut() = Instructions::new(
+            code.iter()
+                .cloned()
+                // Last value on the stack gets returned
+                .chain([Instruction::End])
+                .collect(),
+        );
+    })
+}
+
+/// # Checks that the function returns a single i64, i.e. a packed `(len << 32) | ptr`.
+fn ensure_returns_pointer_length(module: &mut Module, function_name: &str) -> Result<(), String> {
+    let global_function_index = module.get_global_function_index(function_name)?;
+
+    if module.get_function_type(global_function_index)?.results() != [ValueType::I64] {
+        return Err(format!(
+            "Function '{}' doesn't return a packed pointer and length",
+            function_name
+        ));
+    }
+
+    Ok(())
+}
+
+/// # Packs the i32 pointer on top of the stack with the given length into a `(len << 32) | ptr` i64.
+/// # This is the layout the Polkadot host and `sp-runtime-interface` use for pointer-size values,
+/// # with the pointer in the lower 32 bits, rather than `(ptr << 32) | len`.
+fn packed_pointer_length(length: u32) -> Vec<Instruction> {
+    vec![
+        Instruction::I64ExtendUI32,
+        Instruction::I64Const((length as i64) << 32),
+        Instruction::I64Or,
+    ]
+}
+
+/// # Pushes the first address past the memory, i.e. `memory.size * 64KiB`.
+/// # The address doesn't fit in an i32 for a memory of 65536 pages, in which case `u32::MAX` is pushed instead.
+fn first_address_past_memory() -> Vec<Instruction> {
+    vec![
+        Instruction::CurrentMemory(0),
+        Instruction::I32Const(16),
+        Instruction::I32Shl,
+        Instruction::I32Const(-1),
+        // Select the shifted size unless it wrapped around
+        Instruction::CurrentMemory(0),
+        Instruction::I32Const(1 << 16),
+        Instruction::I32LtU,
+        Instruction::Select,
+    ]
+}
+
+/// # Takes a module and replaces the function body with a well-formed but semantically invalid `ValidationResult`.
+/// # The SCALE-encoded result is written into memory allocated through `ext_allocator_malloc`,
+/// # and its packed `(len << 32) | ptr` is returned.
+///
+/// # Errors
+/// - Returns an error if the function doesn't return a single i64.
+/// - Returns an error if the module doesn't import `ext_allocator_malloc`.
+/// - Returns an error if more upward messages are given than `upward_messages` can decode.
+/// - Returns an error if the number of upward messages is given for another fault.
+fn inject_bad_validation_result(
+    module: &mut Module,
+    function_name: &str,
+    fault: Option<ValidationResultFault>,
+    upward_messages: Option<u32>,
+) -> Result<(), String> {
+    let fault = fault.ok_or("No validation result fault given")?;
+    if upward_messages.is_some() && fault != ValidationResultFault::TooManyUpwardMessages {
+        return Err(
+            "The number of upward messages is only used by the `TooManyUpwardMessages` fault"
+                .to_string(),
+        );
+    }
+    let upward_messages = upward_messages.unwrap_or(MAX_UPWARD_MESSAGE_NUM);
+    if upward_messages > MAX_UPWARD_MESSAGE_NUM {
+        return Err(format!(
+            "At most {} upward messages can be decoded",
+            MAX_UPWARD_MESSAGE_NUM
+        ));
+    }
+
+    ensure_returns_pointer_length(module, function_name)?;
+
+    let malloc_index = module.get_malloc_index()? as u32;
+    let pointer_local = module.get_next_local_index(function_name)? as u32;
+    let counter_local = pointer_local + 1;
+
+    let (write_code, length) = write_encoded(
+        &fault.encoded_validation_result(upward_messages),
+        pointer_local,
+        counter_local,
+    );
+
+    module.map_function(function_name, |func_body: &mut FuncBody| {
+        func_body.locals_mut().push(Local::new(2, ValueType::I32));
+
+        *func_body.code_mut() = Instructions::new(
+            [
+                Instruction::I32Const(length as i32),
+                Instruction::Call(malloc_index),
+                Instruction::SetLocal(pointer_local),
+            ]
+            .into_iter()
+            .chain(write_code.iter().cloned())
+            .chain([Instruction::GetLocal(pointer_local)])
+            .chain(packed_pointer_length(length))
+            // Last value on the stack gets returned
+            .chain([Instruction::End])
+            .collect(),
+        );
+    })
+}
+
+/// # Chunk of an encoded value which is written into memory
+#[derive(Clone)]
+enum EncodedChunk {
+    /// Bytes written one by one
+    Bytes(Vec<u8>),
+    /// A byte repeated the given number of times, written in a loop
+    Repeated(u8, u32),
+}
+
+/// # Returns the instructions which write the chunks to the memory the pointer local points to,
+/// # together with the total written length. The counter local is used by the loops of the repeated chunks.
+fn write_encoded(
+    chunks: &[EncodedChunk],
+    pointer_local: u32,
+    counter_local: u32,
+) -> (Vec<Instruction>, u32) {
+    let mut code = vec![];
+    let mut offset = 0_u32;
+
+    for chunk in chunks {
+        match chunk {
+            EncodedChunk::Bytes(bytes) => {
+                code.append(&mut write_bytes(bytes, pointer_local, offset));
+                offset += bytes.len() as u32;
+            }
+            EncodedChunk::Repeated(byte, count) => {
+                code.append(&mut vec![
+                    Instruction::I32Const(0),
+                    Instruction::SetLocal(counter_local),
+                ]);
+                code.append(&mut bounded_loop(
+                    counter_local,
+                    *count,
+                    vec![
+                        Instruction::GetLocal(pointer_local),
+                        Instruction::GetLocal(counter_local),
+                        Instruction::I32Add,
+                        Instruction::I32Const(*byte as i32),
+                        Instruction::I32Store8(0, offset),
+                    ],
+                ));
+                offset += count;
+            }
+        }
+    }
+
+    (code, offset)
+}
+
+/// # Returns the instructions which write the bytes one by one to the memory the pointer local points to,
+/// # starting at the given offset.
+fn write_bytes(bytes: &[u8], pointer_local: u32, offset: u32) -> Vec<Instruction> {
+    bytes
+        .iter()
+        .enumerate()
+        .flat_map(|(position, byte)| {
+            [
+                Instruction::GetLocal(pointer_local),
+                Instruction::I32Const(*byte as i32),
+                Instruction::I32Store8(0, offset + position as u32),
+            ]
+        })
+        .collect()
+}
+
+/// # SCALE-encodes the value as a compact integer.
+fn compact_encode(value: u32) -> Vec<u8> {
+    match value {
+        0..=0x3F => vec![(value << 2) as u8],
+        0x40..=0x3FFF => ((value << 2) as u16 | 0b01).to_le_bytes().to_vec(),
+        0x4000..=0x3FFF_FFFF => ((value << 2) | 0b10).to_le_bytes().to_vec(),
+        _ => [vec![0b11], value.to_le_bytes().to_vec()].concat(),
+    }
+}
+
+/// # Takes a module and injects a call to an imported host function with invalid memory spans in the beginning of the module.
+/// # Following the Polkadot host API conventions, i64 arguments are treated as packed `(len << 32) | ptr` spans
+/// # and i32 arguments as pointers. Float arguments are zero and the results are dropped.
+///
+/// # Errors
+/// - Returns an error if the host function is not imported.
+/// - Returns an error if the overlapping buffers can't be allocated through `ext_allocator_malloc`.
+fn inject_host_function_misuse(
+    module: &mut Module,
+    function_name: &str,
+    host_function: Option<&str>,
+    misuse: Option<HostFunctionMisuse>,
+) -> Result<(), String> {
+    let host_function = host_function.ok_or("No host function given")?;
+    let misuse = misuse.ok_or("No host function misuse given")?;
+
+    let host_function_index = module.get_imported_function_index(host_function)?;
+    let host_function_type = module.get_function_type(host_function_index)?;
+
+    const LENGTH: u32 = 256;
+    let buffer_local = module.get_next_local_index(function_name)? as u32;
+
+    let (locals, mut code) = match misuse {
+        HostFunctionMisuse::OverlappingBuffers => {
+            let malloc_index = module.get_malloc_index()? as u32;
+
+            (
+                vec![Local::new(1, ValueType::I32)],
+                vec![
+                    Instruction::I32Const(LENGTH as i32),
+                    Instruction::Call(malloc_index),
+                    Instruction::SetLocal(buffer_local),
+                ],
+            )
+        }
+        _ => (vec![], vec![]),
+    };
+
+    let pointer = match misuse {
+        // memory.size * 64KiB is the first address past the memory
+        HostFunctionMisuse::PointerBeyondMemory => first_address_past_memory(),
+        HostFunctionMisuse::WrappingLength => vec![Instruction::I32Const(-16)],
+        HostFunctionMisuse::OverlappingBuffers => vec![Instruction::GetLocal(buffer_local)],
+    };
+    let span = match misuse {
+        HostFunctionMisuse::WrappingLength => [
+            vec![Instruction::I32Const(1)],
+            packed_pointer_length(u32::MAX),
+        ]
+        .concat(),
+        _ => [pointer.clone(), packed_pointer_length(LENGTH)].concat(),
+    };
+
+    for param in host_function_type.params() {
+        code.append(&mut match param {
+            ValueType::I32 => pointer.clone(),
+            ValueType::I64 => span.clone(),
+            ValueType::F32 => vec![Instruction::F32Const(0)],
+            ValueType::F64 => vec![Instruction::F64Const(0)],
+        });
+    }
+    code.push(Instruction::Call(host_function_index as u32));
+    code.append(&mut vec![
+        Instruction::Drop;
+        host_function_type.results().len()
+    ]);
+
+    module.map_function(function_name, |func_body: &mut FuncBody| {
+        func_body.locals_mut().append(&mut locals.clone());
+        let code_section = func_body.code_mut();
+
+        let mut code_with_call = code.clone();
+        code_with_call.append(code_section.elements_mut());
+
+        *code_section.elements_mut() = code_with_call;
+    })
+}
+
+/// # Takes a module and injects a loop of storage writes with unique keys in the beginning of the module.
+/// # The key and value buffers are allocated through `ext_allocator_malloc` and the storage is written
+/// # through `ext_storage_set_version_1` as per Polkadot's specification
+/// # https://spec.polkadot.network/chap-host-api#id-ext_storage_set
+fn inject_storage_write_spam(
+    module: &mut Module,
+    function_name: &str,
+    iterations: Option<u32>,
+    value_size: Option<u32>,
+) -> Result<(), String> {
+    let iterations = iterations.ok_or("No iterations given")?;
+    let value_size = value_size.ok_or("No value size given")?;
+
+    const KEY_LENGTH: u32 = 32;
+    // Multiplying by an odd constant is a bijection on u32, so the keys are unique and spread over the trie
+    const KEY_SPREAD: u32 = 0x9E37_79B1;
+
+    let malloc_index = module.get_malloc_index()? as u32;
+    let storage_set_index = module.get_imported_function_index("ext_storage_set_version_1")? as u32;
+    let counter_local = module.get_next_local_index(function_name)? as u32;
+    let key_local = counter_local + 1;
+    let value_local = counter_local + 2;
+
+    let allocate_zeroed = |pointer_local, length| {
+        let (zeroing, _) = write_encoded(
+            &[EncodedChunk::Repeated(0, length)],
+            pointer_local,
+            counter_local,
+        );
+
+        [
+            vec![
+                Instruction::I32Const(length as i32),
+                Instruction::Call(malloc_index),
+                Instruction::SetLocal(pointer_local),
+            ],
+            zeroing,
+        ]
+        .concat()
+    };
+
+    let mut write = vec![
+        // key[0..4] = counter * KEY_SPREAD
+        Instruction::GetLocal(key_local),
+        Instruction::GetLocal(counter_local),
+        Instruction::I32Const(KEY_SPREAD as i32),
+        Instruction::I32Mul,
+        Instruction::I32Store(2, 0),
+    ];
+    if value_size >= 4 {
+        // value[0..4] = counter
+        write.append(&mut vec![
+            Instruction::GetLocal(value_local),
+            Instruction::GetLocal(counter_local),
+            Instruction::I32Store(2, 0),
+        ]);
+    }
+    write.append(
+        &mut [
+            vec![Instruction::GetLocal(key_local)],
+            packed_pointer_length(KEY_LENGTH),
+            vec![Instruction::GetLocal(value_local)],
+            packed_pointer_length(value_size),
+            vec![Instruction::Call(storage_set_index)],
+        ]
+        .concat(),
+    );
+
+    let code = [
+        allocate_zeroed(key_local, KEY_LENGTH),
+        allocate_zeroed(value_local, value_size),
+        vec![
+            Instruction::I32Const(0),
+            Instruction::SetLocal(counter_local),
+        ],
+        bounded_loop(counter_local, iterations, write),
+    ]
+    .concat();
+
+    module.map_function(function_name, |func_body: &mut FuncBody| {
+        func_body.locals_mut().push(Local::new(3, ValueType::I32));
+        let code_section = func_body.code_mut();
+
+        let mut code_with_writes = code.clone();
+        code_with_writes.append(code_section.elements_mut());
+
+        *code_section.elements_mut() = code_with_writes;
+    })
+}
+
+/// Random 32-byte storage key, the blake2-256 hash of `wasm_injector storage proof miss`.
+/// It doesn't match the hashed key of any storage item, so proofs don't contain it.
+const DEFAULT_MISSING_STORAGE_KEY: [u8; 32] = [
+    0x49, 0x63, 0xff, 0x5a, 0xb8, 0x84, 0xfd, 0x15, 0x6c, 0x63, 0x21, 0x17, 0x86, 0xb3, 0xb8, 0x7f,
+    0x6b, 0xab, 0x72, 0x86, 0x4b, 0xce, 0xe8, 0xec, 0x1d, 0x67, 0x7f, 0x35, 0xa8, 0xbb, 0xc6, 0x92,
+];
+
+/// # Takes a module and injects a read of the given storage key in the beginning of the module.
+/// # The key is written into a buffer allocated through `ext_allocator_malloc` and read through
+/// # `ext_storage_get_version_1`, whose result is dropped. Within `validate_block` a key absent
+/// # from the proof makes the runtime panic. Without a given key, `DEFAULT_MISSING_STORAGE_KEY` is read.
+fn inject_storage_proof_miss(
+    module: &mut Module,
+    function_name: &str,
+    storage_key: Option<&str>,
+) -> Result<(), String> {
+    let storage_key = match storage_key {
+        Some(storage_key) => decode_hex(storage_key)?,
+        None => DEFAULT_MISSING_STORAGE_KEY.to_vec(),
+    };
+    let length = storage_key.len() as u32;
+
+    let malloc_index = module.get_malloc_index()? as u32;
+    let storage_get_index = module.get_imported_function_index("ext_storage_get_version_1")? as u32;
+    let key_local = module.get_next_local_index(function_name)? as u32;
+
+    let code = [
+        vec![
+            Instruction::I32Const(length as i32),
+            Instruction::Call(malloc_index),
+            Instruction::SetLocal(key_local),
+        ],
+        write_bytes(&storage_key, key_local, 0),
+        vec![Instruction::GetLocal(key_local)],
+        packed_pointer_length(length),
+        vec![Instruction::Call(storage_get_index), Instruction::Drop],
+    ]
+    .concat();
+
+    module.map_function(function_name, |func_body: &mut FuncBody| {
+        func_body.locals_mut().push(Local::new(1, ValueType::I32));
+        let code_section = func_body.code_mut();
+
+        let mut code_with_read = code.clone();
+        code_with_read.append(code_section.elements_mut());
+
+        *code_section.elements_mut() = code_with_read;
+    })
+}
+
+/// # Decodes a hex string, optionally prefixed with `0x`, into bytes.
+fn decode_hex(hex: &str) -> Result<Vec<u8>, String> {
+    let hex = hex.strip_prefix("0x").unwrap_or(hex);
+    if !hex.len().is_multiple_of(2) {
+        return Err(format!("Odd number of hex digits in `{}`", hex));
+    }
+
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| {
+            hex.get(i..i + 2)
+                .and_then(|byte| u8::from_str_radix(byte, 16).ok())
+                .ok_or(format!("Invalid hex string `{}`", hex))
+        })
+        .collect()
+}
+
+/// # Takes a module and injects a loop of log messages in the beginning of the module.
+/// # The loop is unbounded unless an iteration count is given. The target and the message are written
+/// # into a buffer allocated through `ext_allocator_malloc` and logged through `ext_logging_log_version_1`,
+/// # which is imported if the module doesn't import it yet.
+fn inject_logging_spam(
+    module: &mut Module,
+    function_name: &str,
+    iterations: Option<u32>,
+    message_size: Option<u32>,
+    log_level: Option<LogLevel>,
+) -> Result<(), String> {
+    let message_size = message_size.ok_or("No message size given")?;
+    let log_level = log_level.ok_or("No log level given")?;
+
+    const TARGET: &[u8] = b"wasm_injector";
+
+    let logging_log_index = module.get_or_add_imported_function(
+        "ext_logging_log_version_1",
+        FunctionType::new(vec![ValueType::I32, ValueType::I64, ValueType::I64], vec![]),
+    )? as u32;
+    let malloc_index = module.get_malloc_index()? as u32;
+    let pointer_local = module.get_next_local_index(function_name)? as u32;
+    let counter_local = pointer_local + 1;
+
+    let (write_code, length) = write_encoded(
+        &[
+            EncodedChunk::Bytes(TARGET.to_vec()),
+            EncodedChunk::Repeated(b'x', message_size),
+        ],
+        pointer_local,
+        counter_local,
+    );
+
+    let log = [
+        vec![
+            Instruction::I32Const(log_level as i32),
+            Instruction::GetLocal(pointer_local),
+        ],
+        packed_pointer_length(TARGET.len() as u32),
+        // The message follows the target
+        vec![
+            Instruction::GetLocal(pointer_local),
+            Instruction::I32Const(TARGET.len() as i32),
+            Instruction::I32Add,
+        ],
+        packed_pointer_length(message_size),
+        vec![Instruction::Call(logging_log_index)],
+    ]
+    .concat();
+
+    let logging_loop = match iterations {
+        Some(iterations) => [
+            vec![
+                Instruction::I32Const(0),
+                Instruction::SetLocal(counter_local),
+            ],
+            bounded_loop(counter_local, iterations, log),
+        ]
+        .concat(),
+        None => [
+            vec![Instruction::Loop(BlockType::NoResult)],
+            log,
+            vec![Instruction::Br(0), Instruction::End],
+        ]
+        .concat(),
+    };
+
+    let code = [
+        vec![
+            Instruction::I32Const(length as i32),
+            Instruction::Call(malloc_index),
+            Instruction::SetLocal(pointer_local),
+        ],
+        write_code,
+        logging_loop,
+    ]
+    .concat();
+
+    module.map_function(function_name, |func_body: &mut FuncBody| {
+        func_body.locals_mut().push(Local::new(2, ValueType::I32));
+        let code_section = func_body.code_mut();
+
+        let mut code_with_logs = code.clone();
+        code_with_logs.append(code_section.elements_mut());
+
+        *code_section.elements_mut() = code_with_logs;
+    })
+}
+
+/// # Takes a module and injects a loop of expensive host function calls in the beginning of the module.
+/// # The buffer is allocated through `ext_allocator_malloc` and the host function is imported if the module
+/// # doesn't import it yet. The hashes returned by the host are freed through `ext_allocator_free`.
+fn inject_host_cpu_burn(
+    module: &mut Module,
+    function_name: &str,
+    host_cpu_burn: Option<HostCpuBurn>,
+    iterations: Option<u32>,
+    buffer_size: Option<u32>,
+) -> Result<(), String> {
+    let host_cpu_burn = host_cpu_burn.ok_or("No host cpu burn given")?;
+    let iterations = iterations.ok_or("No iterations given")?;
+    let buffer_size = buffer_size.ok_or("No buffer size given")?;
+
+    const SIGNATURE_LENGTH: u32 = SR25519_SIGNATURE.len() as u32;
+    const PUBLIC_KEY_LENGTH: u32 = SR25519_PUBLIC_KEY.len() as u32;
+
+    let (host_function_name, host_function_type) = host_cpu_burn.host_function();
+    let host_function_index =
+        module.get_or_add_imported_function(host_function_name, host_function_type)? as u32;
+    let malloc_index = module.get_malloc_index()? as u32;
+    let free_index = module.get_free_index()? as u32;
+    let pointer_local = module.get_next_local_index(function_name)? as u32;
+    let counter_local = pointer_local + 1;
+
+    let buffer = || {
+        [
+            vec![Instruction::GetLocal(pointer_local)],
+            packed_pointer_length(buffer_size),
+        ]
+        .concat()
+    };
+    // The contents of the buffer are irrelevant, except for the trie which has to be decodable
+    // and the signature and public key which have to pass the checks done before hashing the message
+    let (setup, call) = match host_cpu_burn {
+        HostCpuBurn::Hashing => (
+            vec![],
+            [
+                buffer(),
+                vec![
+                    Instruction::Call(host_function_index),
+                    Instruction::Call(free_index),
+                ],
+            ]
+            .concat(),
+        ),
+        HostCpuBurn::Sr25519Verify => (
+            write_encoded(
+                &[
+                    // The message is zeroed so that the signature is written after it
+                    EncodedChunk::Repeated(0, buffer_size),
+                    EncodedChunk::Bytes(SR25519_SIGNATURE.to_vec()),
+                    EncodedChunk::Bytes(SR25519_PUBLIC_KEY.to_vec()),
+                ],
+                pointer_local,
+                counter_local,
+            )
+            .0,
+            [
+                // The signature and the public key follow the message
+                vec![
+                    Instruction::GetLocal(pointer_local),
+                    Instruction::I32Const(buffer_size as i32),
+                    Instruction::I32Add,
+                ],
+                buffer(),
+                vec![
+                    Instruction::GetLocal(pointer_local),
+                    Instruction::I32Const((buffer_size + SIGNATURE_LENGTH) as i32),
+                    Instruction::I32Add,
+                    Instruction::Call(host_function_index),
+                    Instruction::Drop,
+                ],
+            ]
+            .concat(),
+        ),
+        HostCpuBurn::TrieRoot => {
+            // A single entry with an empty key holding the rest of the buffer
+            let header = [
+                compact_encode(1),
+                compact_encode(0),
+                compact_encode(buffer_size),
+            ]
+            .concat();
+            let header_length = header.len() as u32;
+            let (write_code, _) =
+                write_encoded(&[EncodedChunk::Bytes(header)], pointer_local, counter_local);
+            (
+                write_code,
+                [
+                    vec![Instruction::GetLocal(pointer_local)],
+                    packed_pointer_length(header_length + buffer_size),
+                    vec![
+                        Instruction::Call(host_function_index),
+                        Instruction::Call(free_index),
+                    ],
+                ]
+                .concat(),
+            )
+        }
+    };
+
+    let allocation_size = match host_cpu_burn {
+        HostCpuBurn::Hashing => buffer_size,
+        HostCpuBurn::Sr25519Verify => buffer_size + SIGNATURE_LENGTH + PUBLIC_KEY_LENGTH,
+        // The header takes at most 1 + 1 + 5 bytes
+        HostCpuBurn::TrieRoot => buffer_size + 7,
+    };
+
+    let code = [
+        vec![
+            Instruction::I32Const(allocation_size as i32),
+            Instruction::Call(malloc_index),
+            Instruction::SetLocal(pointer_local),
+        ],
+        setup,
+        vec![
+            Instruction::I32Const(0),
+            Instruction::SetLocal(counter_local),
+        ],
+        bounded_loop(counter_local, iterations, call),
+    ]
+    .concat();
+
+    module.map_function(function_name, |func_body: &mut FuncBody| {
+        func_body.locals_mut().push(Local::new(2, ValueType::I32));
+        let code_section = func_body.code_mut();
+
+        let mut code_with_calls = code.clone();
+        code_with_calls.append(code_section.elements_mut());
+
+        *code_section.elements_mut() = code_with_calls;
+    })
+}
+
+/// # Takes a module and injects a call to a forbidden host function in the beginning of the module.
+/// # The host function is imported if the module doesn't import it yet and its result is dropped.
+fn inject_forbidden_host_call(
+    module: &mut Module,
+    function_name: &str,
+    forbidden_host_function: Option<ForbiddenHostFunction>,
+) -> Result<(), String> {
+    let forbidden_host_function =
+        forbidden_host_function.ok_or("No forbidden host function given")?;
+
+    let (host_function_name, host_function_type) = forbidden_host_function.host_function();
+    let host_function_index =
+        module.get_or_add_imported_function(host_function_name, host_function_type)? as u32;
+
+    let code = match forbidden_host_function {
+        ForbiddenHostFunction::Timestamp | ForbiddenHostFunction::RandomSeed => {
+            vec![Instruction::Call(host_function_index), Instruction::Drop]
+        }
+        ForbiddenHostFunction::HttpRequestStart => {
+            const METHOD: &[u8] = b"GET";
+            const URI: &[u8] = b"http://localhost";
+            // SCALE-encoded empty list of headers
+            const META: &[u8] = &[0];
+
+            let malloc_index = module.get_malloc_index()? as u32;
+            let pointer_local = module.get_next_local_index(function_name)? as u32;
+            let request = [METHOD, URI, META].concat();
+            let length = request.len() as u32;
+            let write_code = write_bytes(&request, pointer_local, 0);
+
+            let span = |offset: usize, length: usize| {
+                [
+                    vec![
+                        Instruction::GetLocal(pointer_local),
+                        Instruction::I32Const(offset as i32),
+                        Instruction::I32Add,
+                    ],
+                    packed_pointer_length(length as u32),
+                ]
+                .concat()
+            };
+
+            [
+                vec![
+                    Instruction::I32Const(length as i32),
+                    Instruction::Call(malloc_index),
+                    Instruction::SetLocal(pointer_local),
+                ],
+                write_code,
+                span(0, METHOD.len()),
+                span(METHOD.len(), URI.len()),
+                span(METHOD.len() + URI.len(), META.len()),
+                vec![Instruction::Call(host_function_index), Instruction::Drop],
+            ]
+            .concat()
+        }
+    };
+    let locals_count = match forbidden_host_function {
+        ForbiddenHostFunction::HttpRequestStart => 1,
+        _ => 0,
+    };
+
+    module.map_function(function_name, |func_body: &mut FuncBody| {
+        if locals_count > 0 {
+            func_body
+                .locals_mut()
+                .push(Local::new(locals_count, ValueType::I32));
+        }
+        let code_section = func_body.code_mut();
+
+        let mut code_with_call = code.clone();
+        code_with_call.append(code_section.elements_mut());
+
+        *code_section.elements_mut() = code_with_call;
+    })
+}
+
+/// # Takes a module and injects a call to the panic function of the module in the beginning of the module.
+/// # The panic function, `core::panicking::panic` or `core::panicking::panic_str`, is found through
+/// # the `name` custom section and called with the message and a `core::panic::Location`, written into
+/// # a buffer allocated through `ext_allocator_malloc`. The panic goes through `rust_begin_unwind`
+/// # to the panic handler of the runtime, as a real panic does.
+/// # The `Location` is written with the field order rustc currently uses on wasm32, i.e. `file: &str`,
+/// # `line: u32` and `col: u32` at offsets 0, 8 and 12. The struct is `repr(Rust)`, so this isn't guaranteed.
+/// # `rust_begin_unwind` and `core::panicking::panic_fmt` aren't called directly, as building their `PanicInfo`
+/// # or `fmt::Arguments` would rely on far more private layout than the three fields of `Location`.
+fn inject_runtime_panic(
+    module: &mut Module,
+    function_name: &str,
+    panic_message: Option<&str>,
+) -> Result<(), String> {
+    let panic_message = panic_message.ok_or("No panic message given")?;
+
+    const PANIC_FUNCTION_NAMES: [&str; 2] =
+        ["core::panicking::panic", "core::panicking::panic_str"];
+    const FILE: &[u8] = b"wasm_injector";
+    const LINE: i32 = 1;
+    const COLUMN: i32 = 1;
+
+    // Both take the message and the location of the panic
+    let panic_index = PANIC_FUNCTION_NAMES
+        .iter()
+        .find_map(|name| module.get_named_function_index(name).ok())
+        .ok_or("No panic function found in the name section")?;
+    let panic_type = FunctionType::new(vec![ValueType::I32; 3], vec![]);
+    if module.get_function_type(panic_index)? != panic_type {
+        return Err("Unexpected type of the panic function".to_string());
+    }
+
+    let malloc_index = module.get_malloc_index()? as u32;
+    let pointer_local = module.get_next_local_index(function_name)? as u32;
+
+    // The message and the file are followed by the location, aligned to 4 bytes
+    let message = panic_message.as_bytes();
+    let file_offset = message.len() as u32;
+    let location_offset = (file_offset + FILE.len() as u32).next_multiple_of(4);
+    let write_code = write_bytes(&[message, FILE].concat(), pointer_local, 0);
+
+    let store = |offset: u32, value: Vec<Instruction>| {
+        [
+            vec![Instruction::GetLocal(pointer_local)],
+            value,
+            vec![Instruction::I32Store(2, location_offset + offset)],
+        ]
+        .concat()
+    };
+
+    let code = [
+        vec![
+            Instruction::I32Const((location_offset + 16) as i32),
+            Instruction::Call(malloc_index),
+            Instruction::SetLocal(pointer_local),
+        ],
+        write_code,
+        // Location { file: &str, line: u32, col: u32 }
+        store(
+            0,
+            vec![
+                Instruction::GetLocal(pointer_local),
+                Instruction::I32Const(file_offset as i32),
+                Instruction::I32Add,
+            ],
+        ),
+        store(4, vec![Instruction::I32Const(FILE.len() as i32)]),
+        store(8, vec![Instruction::I32Const(LINE)]),
+        store(12, vec![Instruction::I32Const(COLUMN)]),
+        vec![
+            Instruction::GetLocal(pointer_local),
+            Instruction::I32Const(message.len() as i32),
+            Instruction::GetLocal(pointer_local),
+            Instruction::I32Const(location_offset as i32),
+            Instruction::I32Add,
+            Instruction::Call(panic_index as u32),
+        ],
+    ]
+    .concat();
+
+    module.map_function(function_name, |func_body: &mut FuncBody| {
+        func_body.locals_mut().push(Local::new(1, ValueType::I32));
+        let code_section = func_body.code_mut();
+
+        let mut code_with_panic = code.clone();
+        code_with_panic.append(code_section.elements_mut());
+
+        *code_section.elements_mut() = code_with_panic;
+    })
+}
+
+/// # Takes a module and injects a corruption of the `__stack_pointer` global in the beginning of the module.
+///
+/// # Errors
+/// - Returns an error if the `__stack_pointer` global, or `__data_end` when needed, is neither exported nor named.
+fn inject_stack_pointer_corruption(
+    module: &mut Module,
+    function_name: &str,
+    corruption: Option<StackPointerCorruption>,
+) -> Result<(), String> {
+    let corruption = corruption.ok_or("No stack pointer corruption given")?;
+
+    let stack_pointer_index = module.get_global_index("__stack_pointer")? as u32;
+
+    let value = match corruption {
+        StackPointerCorruption::Zero => vec![Instruction::I32Const(0)],
+        StackPointerCorruption::BelowDataEnd => {
+            let data_end_index = module.get_global_index("__data_end")? as u32;
+
+            vec![
+                Instruction::GetGlobal(data_end_index),
+                Instruction::I32Const(1),
+                Instruction::I32Sub,
+                Instruction::I32Const(-16),
+                Instruction::I32And,
+            ]
+        }
+        // An address computed from `memory.size` would wrap around for memories close to 4 GiB
+        StackPointerCorruption::PastMemory => vec![Instruction::I32Const(-16)],
+    };
+
+    module.map_function(function_name, |func_body: &mut FuncBody| {
+        let code = func_body.code_mut();
+
+        let mut code_with_corruption = value.clone();
+        code_with_corruption.push(Instruction::SetGlobal(stack_pointer_index));
+        code_with_corruption.append(code.elements_mut());
+
+        *code.elements_mut() = code_with_corruption;
+    })
+}
+
+/// # Takes a module and injects a memory access in the beginning of the module.
+/// # The address, the static offset, the width, the direction and the alignment hint of the access are
+/// # all given, so that boundary cases can be expressed precisely, e.g. an address at the end of the memory
+/// # or an offset which overflows u32 together with the address.
+///
+/// # Errors
+/// - Returns an error if the alignment hint is larger than the natural alignment, as the module would be invalid.
+fn inject_out_of_bounds_access(
+    module: &mut Module,
+    function_name: &str,
+    address: Option<u32>,
+    offset: Option<u32>,
+    width: Option<AccessWidth>,
+    access: Option<MemoryAccess>,
+    alignment: Option<u32>,
+) -> Result<(), String> {
+    let address = address.ok_or("No address given")?;
+    let width = width.ok_or("No width given")?;
+    let access = access.ok_or("No access given")?;
+    let offset = offset.unwrap_or(0);
+    let alignment = alignment.unwrap_or(width.natural_alignment());
+
+    if alignment > width.natural_alignment() {
+        return Err(format!(
+            "Alignment hint {} is larger than the natural alignment of a {:?} access",
+            alignment, width
+        ));
+    }
+
+    let mut code = vec![Instruction::I32Const(address as i32)];
+    code.append(&mut match (access, width) {
+        (MemoryAccess::Load, AccessWidth::Bits8) => {
+            vec![Instruction::I32Load8U(alignment, offset), Instruction::Drop]
+        }
+        (MemoryAccess::Load, AccessWidth::Bits16) => {
+            vec![
+                Instruction::I32Load16U(alignment, offset),
+                Instruction::Drop,
+            ]
+        }
+        (MemoryAccess::Load, AccessWidth::Bits32) => {
+            vec![Instruction::I32Load(alignment, offset), Instruction::Drop]
+        }
+        (MemoryAccess::Load, AccessWidth::Bits64) => {
+            vec![Instruction::I64Load(alignment, offset), Instruction::Drop]
+        }
+        (MemoryAccess::Store, AccessWidth::Bits8) => vec![
+            Instruction::I32Const(-1),
+            Instruction::I32Store8(alignment, offset),
+        ],
+        (MemoryAccess::Store, AccessWidth::Bits16) => vec![
+            Instruction::I32Const(-1),
+            Instruction::I32Store16(alignment, offset),
+        ],
+        (MemoryAccess::Store, AccessWidth::Bits32) => vec![
+            Instruction::I32Const(-1),
+            Instruction::I32Store(alignment, offset),
+        ],
+        (MemoryAccess::Store, AccessWidth::Bits64) => vec![
+            Instruction::I64Const(-1),
+            Instruction::I64Store(alignment, offset),
+        ],
+    });
+
+    module.map_function(function_name, |func_body: &mut FuncBody| {
+        let code_section = func_body.code_mut();
+
+        let mut code_with_access = code.clone();
+        code_with_access.append(code_section.elements_mut());
+
+        *code_section.elements_mut() = code_with_access;
+    })
+}
+
+#[cfg(test)]
+mod injections_tests {
+    use super::*;
+    use crate::util::{load_module_from_wasm, module_from_blob};
+    use std::path::Path;
+
+    const FUNCTION_NAME: &str = "validate_block";
+    const WASM_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/samples/example.wasm");
+
+    fn get_function_body(module: &mut Module) -> &mut FuncBody {
+        let function_name = "validate_block";
+        let global_function_index = module.get_global_function_index(function_name).unwrap();
+        let import_section_len = module.get_import_section_len().unwrap();
+        let local_function_index = global_function_index - import_section_len;
+        let function_body = module
+            .get_function_body(local_function_index, function_name)
+            .unwrap();
+
+        function_body
+    }
+
+    fn load_module() -> Module {
+        let module_path = Path::new(WASM_PATH);
+        load_module_from_wasm(module_path).unwrap()
+    }
+
+    fn module_from_wat(wat: &str) -> Module {
+        module_from_blob(&wat::parse_str(wat).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_inject_infinite_loop() {
+        let mut module = load_module();
+
+        let injection = Injection::InfiniteLoop;
+        assert!(injection
+            .inject(&mut module, FUNCTION_NAME, &InjectionOptions::default())
+            .is_ok());
+
+        let function_body = get_function_body(&mut module);
+
+        let expected = vec![
+            Instruction::Loop(BlockType::NoResult),
+            Instruction::Nop,
+            Instruction::Br(0),
+            Instruction::End,
+        ];
+        assert!(function_body.code_mut().elements().starts_with(&expected))
+    }
+
+    #[test]
+    fn test_inject_jibberish_return_value() {
+        let mut module = load_module();
+        let injection = Injection::BadReturnValue;
+
+        assert!(injection
+            .inject(&mut module, FUNCTION_NAME, &InjectionOptions::default())
+            .is_ok());
+
+        let function_body = get_function_body(&mut module);
+
+        let expected = vec![Instruction::I64Const(123456789), Instruction::End];
+        assert!(function_body.code_mut().elements().starts_with(&expected))
+    }
+
+    #[test]
+    fn test_inject_bad_return_value_matches_result_type() {
+        let mut module =
+            module_from_wat(r#"(module (func (export "returns_i32") (result i32) (i32.const 0)))"#);
+
+        let injection = Injection::BadReturnValue;
+        assert!(injection
+            .inject(&mut module, "returns_i32", &InjectionOptions::default())
+            .is_ok());
+
+        let function_body = module.get_function_body(0, "returns_i32").unwrap();
+
+        let expected = vec![Instruction::I32Const(123456789), Instruction::End];
+        assert_eq!(function_body.code().elements(), &expected)
+    }
+
+    #[test]
+    fn test_inject_bad_return_value_multiple_results() {
+        let mut module = module_from_wat(
+            r#"(module (func (export "returns_many") (result f32 f64)
+                (f32.const 0) (f64.const 0)))"#,
+        );
+
+        let injection = Injection::BadReturnValue;
+        assert!(injection
+            .inject(&mut module, "returns_many", &InjectionOptions::default())
+            .is_ok());
+
+        let function_body = module.get_function_body(0, "returns_many").unwrap();
+
+        let expected = vec![
+            Instruction::F32Const(123456789_f32.to_bits()),
+            Instruction::F64Const(123456789_f64.to_bits()),
+            Instruction::End,
+        ];
+        assert_eq!(function_body.code().elements(), &expected)
+    }
+
+    #[test]
+    fn test_inject_bad_return_value_requires_result() {
+        let mut module = module_from_wat(r#"(module (func (export "returns_nothing")))"#);
+
+        let injection = Injection::BadReturnValue;
+        assert!(injection
+            .inject(&mut module, "returns_nothing", &InjectionOptions::default())
+            .is_err());
+    }
+
+    #[test]
+    fn test_inject_stack_overflow() {
+        let mut module = load_module();
+
+        let injection = Injection::StackOverflow;
+        assert!(injection
+            .inject(&mut module, FUNCTION_NAME, &InjectionOptions::default())
+            .is_ok());
+
+        let function_body = get_function_body(&mut module);
+
+        let expected = vec![
+            Instruction::GetLocal(2),
+            Instruction::I32Const(2147483647),
+            Instruction::I32Store(2, 81000000),
+        ];
+        assert!(function_body.code_mut().elements().starts_with(&expected))
+    }
+
+    #[test]
+    fn test_inject_noops() {
+        let mut module = load_module();
+
+        let injection = Injection::Noops;
+        let options = InjectionOptions {
+            size: Some(10),
+            ..Default::default()
+        };
+        assert!(injection
+            .inject(&mut module, FUNCTION_NAME, &options)
+            .is_ok());
+
+        let function_body = get_function_body(&mut module);
+
+        let expected = vec![Instruction::Nop; 50]; // 500_000_000 is the actual number of injected instructions
+        assert!(function_body.code_mut().elements().starts_with(&expected))
+    }
+
+    #[test]
+    fn test_inject_heap_overflow() {
+        let mut module = load_module();
+
+        let injection = Injection::HeapOverflow;
+        assert!(injection
+            .inject(&mut module, FUNCTION_NAME, &InjectionOptions::default())
+            .is_ok());
+
+        let index = module.get_malloc_index().unwrap() as u32;
+        let function_body = get_function_body(&mut module);
+
+        let expected = vec![Instruction::I32Const(33_554_431), Instruction::Call(index)]
+            .into_iter()
+            .cycle()
+            .take(16)
+            .collect::<Vec<_>>();
+
+        assert!(function_body.code_mut().elements().starts_with(&expected))
+    }
+
+    #[test]
+    fn test_inject_unreachable() {
+        let mut module = load_module();
+
+        let injection = Injection::Unreachable;
+        assert!(injection
+            .inject(&mut module, FUNCTION_NAME, &InjectionOptions::default())
+            .is_ok());
+
+        let function_body = get_function_body(&mut module);
+
+        let expected = vec![Instruction::Unreachable];
+        assert!(function_body.code_mut().elements().starts_with(&expected))
+    }
+
+    #[test]
+    fn test_inject_arithmetic_trap_div_by_zero() {
+        let mut module = load_module();
+
+        let injection = Injection::ArithmeticTrap;
+        let options = InjectionOptions {
+            trap: Some(ArithmeticTrap::I32DivByZero),
+            ..Default::default()
+        };
+        assert!(injection
+            .inject(&mut module, FUNCTION_NAME, &options)
+            .is_ok());
+
+        let function_body = get_function_body(&mut module);
+
+        let expected = vec![
+            Instruction::I32Const(1),
+            Instruction::I32Const(0),
+            Instruction::I32DivS,
+            Instruction::Drop,
+        ];
+        assert!(function_body.code_mut().elements().starts_with(&expected))
+    }
+
+    #[test]
+    fn test_inject_arithmetic_trap_div_overflow() {
+        let mut module = load_module();
+
+        let injection = Injection::ArithmeticTrap;
+        let options = InjectionOptions {
+            trap: Some(ArithmeticTrap::I64DivOverflow),
+            ..Default::default()
+        };
+        assert!(injection
+            .inject(&mut module, FUNCTION_NAME, &options)
+            .is_ok());
+
+        let function_body = get_function_body(&mut module);
+
+        let expected = vec![
+            Instruction::I64Const(i64::MIN),
+            Instruction::I64Const(-1),
+            Instruction::I64DivS,
+            Instruction::Drop,
+        ];
+        assert!(function_body.code_mut().elements().starts_with(&expected))
+    }
+
+    #[test]
+    fn test_inject_arithmetic_trap_trunc_nan() {
+        let mut module = load_module();
+
+        let injection = Injection::ArithmeticTrap;
+        let options = InjectionOptions {
+            trap: Some(ArithmeticTrap::I32TruncNan),
+            ..Default::default()
+        };
+        assert!(injection
+            .inject(&mut module, FUNCTION_NAME, &options)
+            .is_ok());
+
+        let function_body = get_function_body(&mut module);
+
+        let expected = vec![
+            Instruction::F32Const(f32::NAN.to_bits()),
+            Instruction::I32TruncSF32,
+            Instruction::Drop,
+        ];
+        assert!(function_body.code_mut().elements().starts_with(&expected))
+    }
+
+    #[test]
+    fn test_inject_arithmetic_trap_requires_trap() {
+        let mut module = load_module();
+
+        let injection = Injection::ArithmeticTrap;
+        assert!(injection
+            .inject(&mut module, FUNCTION_NAME, &InjectionOptions::default())
+            .is_err());
+    }
+
+    #[test]
+    fn test_inject_stack_exhaustion() {
+        let mut module = load_module();
+
+        let injection = Injection::StackExhaustion;
+        let options = InjectionOptions {
+            locals: Some(2),
+            ..Default::default()
+        };
+        assert!(injection
+            .inject(&mut module, FUNCTION_NAME, &options)
+            .is_ok());
+
+        let import_section_len = module.get_import_section_len().unwrap();
+        let function_bodies = module.code_section().unwrap().bodies();
+        let recursive_function_index = (import_section_len + function_bodies.len() - 1) as u32;
+
+        let recursive_function_body = function_bodies.last().unwrap();
+        assert_eq!(
+            recursive_function_body.locals(),
+            &[Local::new(2, ValueType::I64)]
+        );
+        assert_eq!(
+            recursive_function_body.code().elements(),
+            &[
+                Instruction::GetLocal(0),
+                Instruction::I64Const(1),
+                Instruction::I64Add,
+                Instruction::SetLocal(1),
+                Instruction::GetLocal(0),
+                Instruction::I64Const(2),
+                Instruction::I64Add,
+                Instruction::SetLocal(2),
+                Instruction::GetLocal(0),
+                Instruction::I64Const(1),
+                Instruction::I64Add,
+                Instruction::Call(recursive_function_index),
+                Instruction::GetLocal(1),
+                Instruction::I64Add,
+                Instruction::GetLocal(2),
+                Instruction::I64Add,
+                Instruction::End,
+            ]
+        );
+
+        let function_body = get_function_body(&mut module);
+
+        let expected = vec![
+            Instruction::I64Const(0),
+            Instruction::Call(recursive_function_index),
+            Instruction::Drop,
+        ];
+        assert!(function_body.code_mut().elements().starts_with(&expected))
+    }
+
+    #[test]
+    fn test_inject_memory_grow_exhaustion() {
+        let mut module = load_module();
+
+        let injection = Injection::MemoryGrowExhaustion;
+        assert!(injection
+            .inject(&mut module, FUNCTION_NAME, &InjectionOptions::default())
+            .is_ok());
+
+        let function_body = get_function_body(&mut module);
+
+        let expected = vec![
+            Instruction::Block(BlockType::NoResult),
+            Instruction::Loop(BlockType::NoResult),
+            Instruction::I32Const(1),
+            Instruction::GrowMemory(0),
+            Instruction::I32Const(-1),
+            Instruction::I32Eq,
+            Instruction::BrIf(1),
+            Instruction::Br(0),
+            Instruction::End,
+            Instruction::End,
+        ];
+        assert!(function_body.code_mut().elements().starts_with(&expected))
+    }
+
+    #[test]
+    fn test_inject_indirect_call_fault_table_index_out_of_bounds() {
+        let mut module = load_module();
+
+        let injection = Injection::IndirectCallFault;
+        let options = InjectionOptions {
+            indirect_call_fault: Some(IndirectCallFault::TableIndexOutOfBounds),
+            ..Default::default()
+        };
+        assert!(injection
+            .inject(&mut module, FUNCTION_NAME, &options)
+            .is_ok());
+
+        let type_index = module
+            .get_or_add_type_index(FunctionType::default())
+            .unwrap() as u32;
+        let function_body = get_function_body(&mut module);
+
+        let expected = vec![
+            Instruction::I32Const(-1),
+            Instruction::CallIndirect(type_index, 0),
+        ];
+        assert!(function_body.code_mut().elements().starts_with(&expected))
+    }
+
+    #[test]
+    fn test_inject_indirect_call_fault_signature_mismatch() {
+        let mut module = load_module();
+
+        let injection = Injection::IndirectCallFault;
+        let options = InjectionOptions {
+            indirect_call_fault: Some(IndirectCallFault::SignatureMismatch),
+            ..Default::default()
+        };
+        assert!(injection
+            .inject(&mut module, FUNCTION_NAME, &options)
+            .is_ok());
+
+        let type_index = module
+            .get_or_add_type_index(FunctionType::default())
+            .unwrap() as u32;
+        let function_body = get_function_body(&mut module);
+
+        let expected = vec![
+            Instruction::I32Const(1),
+            Instruction::CallIndirect(type_index, 0),
+        ];
+        assert!(function_body.code_mut().elements().starts_with(&expected))
+    }
+
+    #[test]
+    fn test_inject_indirect_call_fault_null_element() {
+        let mut module = load_module();
+
+        let injection = Injection::IndirectCallFault;
+        let options = InjectionOptions {
+            indirect_call_fault: Some(IndirectCallFault::NullElement),
+            ..Default::default()
+        };
+        assert!(injection
+            .inject(&mut module, FUNCTION_NAME, &options)
+            .is_ok());
+
+        let type_index = module
+            .get_or_add_type_index(FunctionType::default())
+            .unwrap() as u32;
+        let function_body = get_function_body(&mut module);
+
+        let expected = vec![
+            Instruction::I32Const(0),
+            Instruction::CallIndirect(type_index, 0),
+        ];
+        assert!(function_body.code_mut().elements().starts_with(&expected))
+    }
+
+    #[test]
+    fn test_inject_indirect_call_fault_null_element_of_unknown_table() {
+        let options = InjectionOptions {
+            indirect_call_fault: Some(IndirectCallFault::NullElement),
+            ..Default::default()
+        };
+
+        for table in [
+            r#"(import "env" "table" (table 2 2 funcref))"#,
+            "(table 2 funcref)",
+            "(table 2 4 funcref)",
+        ] {
+            let mut module = module_from_wat(&format!(
+                r#"(module {} (func (export "{}")))"#,
+                table, FUNCTION_NAME
+            ));
+            assert!(Injection::IndirectCallFault
+                .inject(&mut module, FUNCTION_NAME, &options)
+                .is_err());
+        }
+    }
+
+    #[test]
+    fn test_inject_bounded_loop() {
+        let mut module = load_module();
+        let counter = module.get_next_local_index(FUNCTION_NAME).unwrap() as u32;
+
+        let injection = Injection::BoundedLoop;
+        let options = InjectionOptions {
+            iterations: Some(1_000_000),
+            ..Default::default()
+        };
+        assert!(injection
+            .inject(&mut module, FUNCTION_NAME, &options)
+            .is_ok());
+
+        let function_body = get_function_body(&mut module);
+        assert_eq!(
+            function_body.locals().last(),
+            Some(&Local::new(1, ValueType::I32))
+        );
+
+        let expected = vec![
+            Instruction::Block(BlockType::NoResult),
+            Instruction::Loop(BlockType::NoResult),
+            Instruction::GetLocal(counter),
+            Instruction::I32Const(1_000_000),
+            Instruction::I32GeU,
+            Instruction::BrIf(1),
+            Instruction::Nop,
+            Instruction::GetLocal(counter),
+            Instruction::I32Const(1),
+            Instruction::I32Add,
+            Instruction::SetLocal(counter),
+            Instruction::Br(0),
+            Instruction::End,
+            Instruction::End,
+        ];
+        assert!(function_body.code_mut().elements().starts_with(&expected))
+    }
+
+    #[test]
+    fn test_inject_bad_pointer_length_pointer_beyond_memory() {
+        let mut module = load_module();
+
+        let injection = Injection::BadPointerLength;
+        let options = InjectionOptions {
+            pointer_length_fault: Some(PointerLengthFault::PointerBeyondMemory),
+            ..Default::default()
+        };
+        assert!(injection
+            .inject(&mut module, FUNCTION_NAME, &options)
+            .is_ok());
+
+        let function_body = get_function_body(&mut module);
+
+        // A 4 GiB memory has no address past it, so `u32::MAX` is selected instead of the wrapped size
+        let expected = [
+            Instruction::CurrentMemory(0),
+            Instruction::I32Const(16),
+            Instruction::I32Shl,
+            Instruction::I32Const(-1),
+            Instruction::CurrentMemory(0),
+            Instruction::I32Const(65536),
+            Instruction::I32LtU,
+            Instruction::Select,
+        ];
+        assert!(function_body.code().elements().starts_with(&expected))
+    }
+
+    #[test]
+    fn test_inject_bad_pointer_length_overflow() {
+        let mut module = load_module();
+
+        let injection = Injection::BadPointerLength;
+        let options = InjectionOptions {
+            pointer_length_fault: Some(PointerLengthFault::LengthOverflow),
+            ..Default::default()
+        };
+        assert!(injection
+            .inject(&mut module, FUNCTION_NAME, &options)
+            .is_ok());
+
+        let function_body = get_function_body(&mut module);
+
+        let expected = vec![
+            Instruction::I32Const(8),
+            Instruction::I64ExtendUI32,
+            Instruction::I64Const((u32::MAX as i64) << 32),
+            Instruction::I64Or,
+            Instruction::End,
+        ];
+        assert_eq!(function_body.code().elements(), &expected)
+    }
+
+    #[test]
+    fn test_inject_bad_pointer_length_into_stack() {
+        let mut module = load_module();
+
+        let injection = Injection::BadPointerLength;
+        let options = InjectionOptions {
+            pointer_length_fault: Some(PointerLengthFault::PointerIntoStack),
+            ..Default::default()
+        };
+        assert!(injection
+            .inject(&mut module, FUNCTION_NAME, &options)
+            .is_ok());
+
+        let stack_pointer_index = module.get_global_index("__stack_pointer").unwrap() as u32;
+        let function_body = get_function_body(&mut module);
+
+        let expected = vec![
+            Instruction::GetGlobal(stack_pointer_index),
+            Instruction::I32Const(32),
+            Instruction::I32Sub,
+            Instruction::I64ExtendUI32,
+            Instruction::I64Const(32 << 32),
+            Instruction::I64Or,
+            Instruction::End,
+        ];
+        assert_eq!(function_body.code().elements(), &expected)
+    }
+
+    #[test]
+    fn test_inject_bad_pointer_length_non_scale_garbage() {
+        let mut module = load_module();
+        let pointer_local = module.get_next_local_index(FUNCTION_NAME).unwrap() as u32;
+
+        let injection = Injection::BadPointerLength;
+        let options = InjectionOptions {
+            pointer_length_fault: Some(PointerLengthFault::NonScaleGarbage),
+            ..Default::default()
+        };
+        assert!(injection
+            .inject(&mut module, FUNCTION_NAME, &options)
+            .is_ok());
+
+        let malloc_index = module.get_malloc_index().unwrap() as u32;
+        let function_body = get_function_body(&mut module);
+
+        let expected = vec![
+            Instruction::I32Const(32),
+            Instruction::Call(malloc_index),
+            Instruction::SetLocal(pointer_local),
+            Instruction::GetLocal(pointer_local),
+            Instruction::I64Const(-1),
+            Instruction::I64Store(3, 0),
+        ];
+        assert!(function_body.code_mut().elements().starts_with(&expected))
+    }
+
+    #[test]
+    fn test_inject_bad_pointer_length_requires_i64_result() {
+        let mut module =
+            module_from_wat(r#"(module (func (export "returns_i32") (result i32) (i32.const 0)))"#);
+
+        let injection = Injection::BadPointerLength;
+        let options = InjectionOptions {
+            pointer_length_fault: Some(PointerLengthFault::ZeroLength),
+            ..Default::default()
+        };
+        assert!(injection
+            .inject(&mut module, "returns_i32", &options)
+            .is_err());
+    }
+
+    #[test]
+    fn test_compact_encode() {
+        assert_eq!(compact_encode(0), vec![0x00]);
+        assert_eq!(compact_encode(63), vec![0xFC]);
+        assert_eq!(compact_encode(64), vec![0x01, 0x01]);
+        assert_eq!(compact_encode(16384), vec![0x02, 0x00, 0x01, 0x00]);
+        assert_eq!(compact_encode(u32::MAX), vec![0x03, 0xFF, 0xFF, 0xFF, 0xFF]);
+    }
+
+    #[test]
+    fn test_inject_bad_validation_result() {
+        let mut module = load_module();
+        let pointer_local = module.get_next_local_index(FUNCTION_NAME).unwrap() as u32;
+
+        let injection = Injection::BadValidationResult;
+        let options = InjectionOptions {
+            validation_result_fault: Some(ValidationResultFault::InvalidProcessedDownwardMessages),
+            ..Default::default()
+        };
+        assert!(injection
+            .inject(&mut module, FUNCTION_NAME, &options)
+            .is_ok());
+
+        let malloc_index = module.get_malloc_index().unwrap() as u32;
+        let function_body = get_function_body(&mut module);
+
+        // Empty `head_data`, no `new_validation_code`, no messages,
+        // `u32::MAX` processed downward messages and a zero watermark
+        let encoded = [0_u8, 0, 0, 0, 0xFF, 0xFF, 0xFF, 0xFF, 0, 0, 0, 0];
+        let expected = [
+            Instruction::I32Const(encoded.len() as i32),
+            Instruction::Call(malloc_index),
+            Instruction::SetLocal(pointer_local),
+        ]
+        .into_iter()
+        .chain(encoded.iter().enumerate().flat_map(|(offset, byte)| {
+            [
+                Instruction::GetLocal(pointer_local),
+                Instruction::I32Const(*byte as i32),
+                Instruction::I32Store8(0, offset as u32),
+            ]
+        }))
+        .chain([
+            Instruction::GetLocal(pointer_local),
+            Instruction::I64ExtendUI32,
+            Instruction::I64Const((encoded.len() as i64) << 32),
+            Instruction::I64Or,
+            Instruction::End,
+        ])
+        .collect::<Vec<_>>();
+        assert_eq!(function_body.code().elements(), &expected)
+    }
+
+    #[test]
+    fn test_inject_bad_validation_result_oversized_code() {
+        let mut module = load_module();
+
+        let injection = Injection::BadValidationResult;
+        let options = InjectionOptions {
+            validation_result_fault: Some(ValidationResultFault::OversizedValidationCode),
+            ..Default::default()
+        };
+        assert!(injection
+            .inject(&mut module, FUNCTION_NAME, &options)
+            .is_ok());
+
+        let function_body = get_function_body(&mut module);
+
+        // Empty `head_data` and `Some` code of 3 MiB + 1 bytes, followed by 10 more bytes
+        let expected_length = 1 + 1 + 4 + 3 * 1024 * 1024 + 1 + 10;
+        assert_eq!(
+            function_body.code().elements().first(),
+            Some(&Instruction::I32Const(expected_length))
+        )
+    }
+
+    #[test]
+    fn test_inject_bad_validation_result_too_many_upward_messages() {
+        let mut module = load_module();
+
+        let injection = Injection::BadValidationResult;
+        let options = InjectionOptions {
+            validation_result_fault: Some(ValidationResultFault::TooManyUpwardMessages),
+            upward_messages: Some(1000),
+            ..Default::default()
+        };
+        assert!(injection
+            .inject(&mut module, FUNCTION_NAME, &options)
+            .is_ok());
+
+        let function_body = get_function_body(&mut module);
+
+        // Empty `head_data` and no code, 1000 empty messages, followed by 9 more bytes
+        let expected_length = 1 + 1 + 2 + 1000 + 9;
+        assert_eq!(
+            function_body.code().elements().first(),
+            Some(&Instruction::I32Const(expected_length))
+        );
+
+        let options = InjectionOptions {
+            validation_result_fault: Some(ValidationResultFault::TooManyUpwardMessages),
+            upward_messages: Some(MAX_UPWARD_MESSAGE_NUM + 1),
+            ..Default::default()
+        };
+        assert!(Injection::BadValidationResult
+            .inject(&mut load_module(), FUNCTION_NAME, &options)
+            .is_err());
+
+        let options = InjectionOptions {
+            validation_result_fault: Some(ValidationResultFault::WrongHeadData),
+            upward_messages: Some(1000),
+            ..Default::default()
+        };
+        assert!(Injection::BadValidationResult
+            .inject(&mut load_module(), FUNCTION_NAME, &options)
+            .is_err());
+    }
+
+    #[test]
+    fn test_inject_host_function_misuse() {
+        let mut module = load_module();
+
+        let injection = Injection::HostFunctionMisuse;
+        let options = InjectionOptions {
+            host_function: Some("ext_storage_get".to_string()),
+            host_function_misuse: Some(HostFunctionMisuse::WrappingLength),
+            ..Default::default()
+        };
+        assert!(injection
+            .inject(&mut module, FUNCTION_NAME, &options)
+            .is_ok());
+
+        let storage_get_index = module
+            .get_imported_function_index("ext_storage_get")
+            .unwrap() as u32;
+        let function_body = get_function_body(&mut module);
+
+        // (key: i64) -> i64
+        let expected = vec![
+            Instruction::I32Const(1),
+            Instruction::I64ExtendUI32,
+            Instruction::I64Const((u32::MAX as i64) << 32),
+            Instruction::I64Or,
+            Instruction::Call(storage_get_index),
+            Instruction::Drop,
+        ];
+        assert!(function_body.code_mut().elements().starts_with(&expected))
+    }
+
+    #[test]
+    fn test_inject_host_function_misuse_overlapping_buffers() {
+        let mut module = load_module();
+        let buffer_local = module.get_next_local_index(FUNCTION_NAME).unwrap() as u32;
+
+        let injection = Injection::HostFunctionMisuse;
+        let options = InjectionOptions {
+            host_function: Some("ext_crypto_sr25519_verify_version_2".to_string()),
+            host_function_misuse: Some(HostFunctionMisuse::OverlappingBuffers),
+            ..Default::default()
+        };
+        assert!(injection
+            .inject(&mut module, FUNCTION_NAME, &options)
+            .is_ok());
+
+        let malloc_index = module.get_malloc_index().unwrap() as u32;
+        let verify_index = module
+            .get_imported_function_index("ext_crypto_sr25519_verify_version_2")
+            .unwrap() as u32;
+        let function_body = get_function_body(&mut module);
+
+        // (signature: i32, message: i64, public_key: i32) -> i32
+        let span = [
+            Instruction::GetLocal(buffer_local),
+            Instruction::I64ExtendUI32,
+            Instruction::I64Const(256 << 32),
+            Instruction::I64Or,
+        ];
+        let expected = [
+            Instruction::I32Const(256),
+            Instruction::Call(malloc_index),
+            Instruction::SetLocal(buffer_local),
+            Instruction::GetLocal(buffer_local),
+        ]
+        .into_iter()
+        .chain(span)
+        .chain([
+            Instruction::GetLocal(buffer_local),
+            Instruction::Call(verify_index),
+            Instruction::Drop,
+        ])
+        .collect::<Vec<_>>();
+        assert!(function_body.code_mut().elements().starts_with(&expected))
+    }
+
+    #[test]
+    fn test_inject_host_function_misuse_requires_import() {
+        let mut module = load_module();
+
+        let injection = Injection::HostFunctionMisuse;
+        let options = InjectionOptions {
+            host_function: Some("ext_no_such_function".to_string()),
+            host_function_misuse: Some(HostFunctionMisuse::PointerBeyondMemory),
+            ..Default::default()
+        };
+        assert!(injection
+            .inject(&mut module, FUNCTION_NAME, &options)
+            .is_err());
+    }
+
+    #[test]
+    fn test_inject_allocator_misuse_double_free() {
+        let mut module = load_module();
+        let pointer_local = module.get_next_local_index(FUNCTION_NAME).unwrap() as u32;
+
+        let injection = Injection::AllocatorMisuse;
+        let options = InjectionOptions {
+            allocator_misuse: Some(AllocatorMisuse::DoubleFree),
+            ..Default::default()
+        };
+        assert!(injection
+            .inject(&mut module, FUNCTION_NAME, &options)
+            .is_ok());
+
+        let malloc_index = module.get_malloc_index().unwrap() as u32;
+        let free_index = module.get_free_index().unwrap() as u32;
+        let function_body = get_function_body(&mut module);
+
+        let expected = vec![
+            Instruction::I32Const(64),
+            Instruction::Call(malloc_index),
+            Instruction::SetLocal(pointer_local),
+            Instruction::GetLocal(pointer_local),
+            Instruction::Call(free_index),
+            Instruction::GetLocal(pointer_local),
+            Instruction::Call(free_index),
+        ];
+        assert!(function_body.code_mut().elements().starts_with(&expected))
+    }
+
+    #[test]
+    fn test_inject_allocator_misuse_use_after_free() {
+        let mut module = load_module();
+        let pointer_local = module.get_next_local_index(FUNCTION_NAME).unwrap() as u32;
+
+        let injection = Injection::AllocatorMisuse;
+        let options = InjectionOptions {
+            allocator_misuse: Some(AllocatorMisuse::UseAfterFree),
+            ..Default::default()
+        };
+        assert!(injection
+            .inject(&mut module, FUNCTION_NAME, &options)
+            .is_ok());
+
+        let malloc_index = module.get_malloc_index().unwrap() as u32;
+        let free_index = module.get_free_index().unwrap() as u32;
+        let function_body = get_function_body(&mut module);
+
+        let expected = vec![
+            Instruction::I32Const(64),
+            Instruction::Call(malloc_index),
+            Instruction::SetLocal(pointer_local),
+            Instruction::GetLocal(pointer_local),
+            Instruction::Call(free_index),
+            Instruction::GetLocal(pointer_local),
+            Instruction::I64Const(-1),
+            Instruction::I64Store(3, 0),
+        ];
+        assert!(function_body.code_mut().elements().starts_with(&expected))
+    }
+
+    #[test]
+    fn test_inject_allocator_misuse_invalid_free() {
+        let mut module = load_module();
+        let pointer_local = module.get_next_local_index(FUNCTION_NAME).unwrap() as u32;
+
+        let injection = Injection::AllocatorMisuse;
+        let options = InjectionOptions {
+            allocator_misuse: Some(AllocatorMisuse::InvalidFree),
+            ..Default::default()
+        };
+        assert!(injection
+            .inject(&mut module, FUNCTION_NAME, &options)
+            .is_ok());
+
+        let malloc_index = module.get_malloc_index().unwrap() as u32;
+        let free_index = module.get_free_index().unwrap() as u32;
+        let function_body = get_function_body(&mut module);
+
+        let expected = vec![
+            Instruction::I32Const(64),
+            Instruction::Call(malloc_index),
+            Instruction::SetLocal(pointer_local),
+            Instruction::GetLocal(pointer_local),
+            Instruction::I32Const(32),
+            Instruction::I32Add,
+            Instruction::Call(free_index),
+        ];
+        assert!(function_body.code_mut().elements().starts_with(&expected))
+    }
+
+    #[test]
+    fn test_inject_allocator_header_corruption() {
+        let mut module = load_module();
+        let pointer_local = module.get_next_local_index(FUNCTION_NAME).unwrap() as u32;
+
+        let injection = Injection::AllocatorHeaderCorruption;
+        let options = InjectionOptions {
+            header_corruption: Some(HeaderCorruption::BadOrder),
+            ..Default::default()
+        };
+        assert!(injection
+            .inject(&mut module, FUNCTION_NAME, &options)
+            .is_ok());
+
+        let malloc_index = module.get_malloc_index().unwrap() as u32;
+        let free_index = module.get_free_index().unwrap() as u32;
+        let function_body = get_function_body(&mut module);
+
+        let expected = vec![
+            Instruction::I32Const(64),
+            Instruction::Call(malloc_index),
+            Instruction::SetLocal(pointer_local),
+            Instruction::GetLocal(pointer_local),
+            Instruction::I32Const(8),
+            Instruction::I32Sub,
+            Instruction::I64Const((1 << 32) | 23),
+            Instruction::I64Store(3, 0),
+            Instruction::GetLocal(pointer_local),
+            Instruction::Call(free_index),
+        ];
+        assert!(function_body.code_mut().elements().starts_with(&expected))
+    }
+
+    #[test]
+    fn test_inject_allocator_header_corruption_free_list_pointer() {
+        let mut module = load_module();
+        let pointer_local = module.get_next_local_index(FUNCTION_NAME).unwrap() as u32;
+
+        let injection = Injection::AllocatorHeaderCorruption;
+        let options = InjectionOptions {
+            header_corruption: Some(HeaderCorruption::FreeListPointer),
+            ..Default::default()
+        };
+        assert!(injection
+            .inject(&mut module, FUNCTION_NAME, &options)
+            .is_ok());
+
+        let function_body = get_function_body(&mut module);
+
+        let expected = vec![
+            Instruction::GetLocal(pointer_local),
+            Instruction::I32Const(8),
+            Instruction::I32Sub,
+            Instruction::GetLocal(pointer_local),
+            Instruction::I32Const(8),
+            Instruction::I32Sub,
+            Instruction::I64ExtendUI32,
+            Instruction::I64Store(3, 0),
+        ];
+        assert_eq!(&function_body.code().elements()[3..11], &expected)
+    }
+
+    #[test]
+    fn test_inject_allocator_fragmentation() {
+        let mut module = load_module();
+        let counter_local = module.get_next_local_index(FUNCTION_NAME).unwrap() as u32;
+        let pointer_local = counter_local + 1;
+
+        let injection = Injection::AllocatorFragmentation;
+        let options = InjectionOptions {
+            allocation_sizes: vec![16, 4096],
+            iterations: Some(1000),
+            free_every: Some(2),
+            ..Default::default()
+        };
+        assert!(injection
+            .inject(&mut module, FUNCTION_NAME, &options)
+            .is_ok());
+
+        let malloc_index = module.get_malloc_index().unwrap() as u32;
+        let free_index = module.get_free_index().unwrap() as u32;
+        let function_body = get_function_body(&mut module);
+        // The counter, the new block and the previous block of both sizes
+        assert_eq!(
+            function_body.locals().last(),
+            Some(&Local::new(4, ValueType::I32))
+        );
+
+        // The block freed is the one of the previous iteration, after the new block is allocated
+        let allocation = |size, previous_local| {
+            vec![
+                Instruction::I32Const(size),
+                Instruction::Call(malloc_index),
+                Instruction::SetLocal(pointer_local),
+                Instruction::GetLocal(counter_local),
+                Instruction::I32Const(2),
+                Instruction::I32RemU,
+                Instruction::I32Eqz,
+                Instruction::GetLocal(previous_local),
+                Instruction::I32Const(0),
+                Instruction::I32Ne,
+                Instruction::I32And,
+                Instruction::If(BlockType::NoResult),
+                Instruction::GetLocal(previous_local),
+                Instruction::Call(free_index),
+                Instruction::End,
+                Instruction::GetLocal(pointer_local),
+                Instruction::SetLocal(previous_local),
+            ]
+        };
+        let expected = [
+            vec![
+                Instruction::Block(BlockType::NoResult),
+                Instruction::Loop(BlockType::NoResult),
+                Instruction::GetLocal(counter_local),
+                Instruction::I32Const(1000),
+                Instruction::I32GeU,
+                Instruction::BrIf(1),
+            ],
+            allocation(16, pointer_local + 1),
+            allocation(4096, pointer_local + 2),
+        ]
+        .concat();
+        assert!(function_body.code_mut().elements().starts_with(&expected))
+    }
+
+    #[test]
+    fn test_inject_allocator_fragmentation_free_every_zero() {
+        let mut module = load_module();
+
+        let injection = Injection::AllocatorFragmentation;
+        let options = InjectionOptions {
+            allocation_sizes: vec![32],
+            iterations: Some(10),
+            free_every: Some(0),
+            ..Default::default()
+        };
+        assert!(injection
+            .inject(&mut module, FUNCTION_NAME, &options)
+            .is_err());
+    }
+
+    #[test]
+    fn test_inject_allocator_fragmentation_without_frees() {
+        let mut module = load_module();
+
+        let injection = Injection::AllocatorFragmentation;
+        let options = InjectionOptions {
+            allocation_sizes: vec![32],
+            iterations: Some(10),
+            ..Default::default()
+        };
+        assert!(injection
+            .inject(&mut module, FUNCTION_NAME, &options)
+            .is_ok());
+
+        let malloc_index = module.get_malloc_index().unwrap() as u32;
+        let function_body = get_function_body(&mut module);
+
+        let expected = vec![
+            Instruction::I32Const(32),
+            Instruction::Call(malloc_index),
+            Instruction::Drop,
+        ];
+        assert_eq!(&function_body.code().elements()[6..9], &expected)
+    }
+
+    #[test]
+    fn test_inject_storage_write_spam() {
+        let mut module = load_module();
+        let counter_local = module.get_next_local_index(FUNCTION_NAME).unwrap() as u32;
+        let key_local = counter_local + 1;
+        let value_local = counter_local + 2;
+
+        let injection = Injection::StorageWriteSpam;
+        let options = InjectionOptions {
+            iterations: Some(10_000),
+            value_size: Some(1024),
+            ..Default::default()
+        };
+        assert!(injection
+            .inject(&mut module, FUNCTION_NAME, &options)
+            .is_ok());
+
+        let malloc_index = module.get_malloc_index().unwrap() as u32;
+        let storage_set_index = module
+            .get_imported_function_index("ext_storage_set_version_1")
+            .unwrap() as u32;
+        let function_body = get_function_body(&mut module);
+        assert_eq!(
+            function_body.locals().last(),
+            Some(&Local::new(3, ValueType::I32))
+        );
+
+        let code = function_body.code().elements();
+        assert!(code.starts_with(&[
+            Instruction::I32Const(32),
+            Instruction::Call(malloc_index),
+            Instruction::SetLocal(key_local),
+        ]));
+
+        let write = vec![
+            Instruction::GetLocal(key_local),
+            Instruction::GetLocal(counter_local),
+            Instruction::I32Const(0x9E37_79B1_u32 as i32),
+            Instruction::I32Mul,
+            Instruction::I32Store(2, 0),
+            Instruction::GetLocal(value_local),
+            Instruction::GetLocal(counter_local),
+            Instruction::I32Store(2, 0),
+            Instruction::GetLocal(key_local),
+            Instruction::I64ExtendUI32,
+            Instruction::I64Const(32 << 32),
+            Instruction::I64Or,
+            Instruction::GetLocal(value_local),
+            Instruction::I64ExtendUI32,
+            Instruction::I64Const(1024 << 32),
+            Instruction::I64Or,
+            Instruction::Call(storage_set_index),
+        ];
+        assert!(code.windows(write.len()).any(|window| window == write));
+    }
+
+    #[test]
+    fn test_inject_storage_proof_miss() {
+        let mut module = load_module();
+        let key_local = module.get_next_local_index(FUNCTION_NAME).unwrap() as u32;
+
+        let injection = Injection::StorageProofMiss;
+        let options = InjectionOptions {
+            storage_key: Some("0xdead".to_string()),
+            ..Default::default()
+        };
+        assert!(injection
+            .inject(&mut module, FUNCTION_NAME, &options)
+            .is_ok());
+
+        let malloc_index = module.get_malloc_index().unwrap() as u32;
+        let storage_get_index = module
+            .get_imported_function_index("ext_storage_get_version_1")
+            .unwrap() as u32;
+        let function_body = get_function_body(&mut module);
+        assert_eq!(
+            function_body.locals().last(),
+            Some(&Local::new(1, ValueType::I32))
+        );
+        assert!(function_body.code().elements().starts_with(&[
+            Instruction::I32Const(2),
+            Instruction::Call(malloc_index),
+            Instruction::SetLocal(key_local),
+            Instruction::GetLocal(key_local),
+            Instruction::I32Const(0xde),
+            Instruction::I32Store8(0, 0),
+            Instruction::GetLocal(key_local),
+            Instruction::I32Const(0xad),
+            Instruction::I32Store8(0, 1),
+            Instruction::GetLocal(key_local),
+            Instruction::I64ExtendUI32,
+            Instruction::I64Const(2 << 32),
+            Instruction::I64Or,
+            Instruction::Call(storage_get_index),
+            Instruction::Drop,
+        ]));
+    }
+
+    #[test]
+    fn test_inject_storage_proof_miss_default_key() {
+        let mut module = load_module();
+        let key_local = module.get_next_local_index(FUNCTION_NAME).unwrap() as u32;
+
+        let injection = Injection::StorageProofMiss;
+        assert!(injection
+            .inject(&mut module, FUNCTION_NAME, &InjectionOptions::default())
+            .is_ok());
+
+        let function_body = get_function_body(&mut module);
+        let code = function_body.code().elements();
+        assert_eq!(code.first(), Some(&Instruction::I32Const(32)));
+
+        let key_writes = write_bytes(&DEFAULT_MISSING_STORAGE_KEY, key_local, 0);
+        assert!(code[3..].starts_with(&key_writes));
+    }
+
+    #[test]
+    fn test_inject_storage_proof_miss_invalid_key() {
+        let mut module = load_module();
+
+        for storage_key in ["0xabc", "0xzz"] {
+            let options = InjectionOptions {
+                storage_key: Some(storage_key.to_string()),
+                ..Default::default()
+            };
+            assert!(Injection::StorageProofMiss
+                .inject(&mut module, FUNCTION_NAME, &options)
+                .is_err());
+        }
+    }
+
+    #[test]
+    fn test_inject_logging_spam() {
+        let mut module = load_module();
+        let pointer_local = module.get_next_local_index(FUNCTION_NAME).unwrap() as u32;
+
+        let injection = Injection::LoggingSpam;
+        let options = InjectionOptions {
+            message_size: Some(4096),
+            log_level: Some(LogLevel::Warn),
+            ..Default::default()
+        };
+        assert!(injection
+            .inject(&mut module, FUNCTION_NAME, &options)
+            .is_ok());
+
+        let logging_log_index = module
+            .get_imported_function_index("ext_logging_log_version_1")
+            .unwrap() as u32;
+        let code = get_function_body(&mut module).code().elements();
+
+        let log = vec![
+            Instruction::I32Const(1),
+            Instruction::GetLocal(pointer_local),
+            Instruction::I64ExtendUI32,
+            Instruction::I64Const(13 << 32),
+            Instruction::I64Or,
+            Instruction::GetLocal(pointer_local),
+            Instruction::I32Const(13),
+            Instruction::I32Add,
+            Instruction::I64ExtendUI32,
+            Instruction::I64Const(4096 << 32),
+            Instruction::I64Or,
+            Instruction::Call(logging_log_index),
+            Instruction::Br(0),
+            Instruction::End,
+        ];
+        assert!(code.windows(log.len()).any(|window| window == log));
+    }
+
+    #[test]
+    fn test_inject_logging_spam_adds_import() {
+        let mut module = module_from_wat(
+            r#"
+            (module
+                (import "env" "ext_allocator_malloc_version_1" (func (param i32) (result i32)))
+                (import "env" "memory" (memory 1))
+                (func $helper)
+                (func (export "validate_block") (param i32 i32) (result i64)
+                    call $helper
+                    i64.const 0)
+            )
+            "#,
+        );
+
+        let injection = Injection::LoggingSpam;
+        let options = InjectionOptions {
+            iterations: Some(10),
+            message_size: Some(1),
+            log_level: Some(LogLevel::Error),
+            ..Default::default()
+        };
+        assert!(injection
+            .inject(&mut module, FUNCTION_NAME, &options)
+            .is_ok());
+
+        assert_eq!(
+            module
+                .get_imported_function_index("ext_logging_log_version_1")
+                .unwrap(),
+            1
+        );
+        // The call to the local function is shifted by the new import
+        let code = get_function_body(&mut module).code().elements();
+        assert!(code.ends_with(&[
+            Instruction::Call(2),
+            Instruction::I64Const(0),
+            Instruction::End,
+        ]));
+    }
+
+    #[test]
+    fn test_inject_host_cpu_burn() {
+        let mut module = load_module();
+        let pointer_local = module.get_next_local_index(FUNCTION_NAME).unwrap() as u32;
+
+        let injection = Injection::HostCpuBurn;
+        let options = InjectionOptions {
+            host_cpu_burn: Some(HostCpuBurn::Hashing),
+            iterations: Some(1000),
+            buffer_size: Some(1 << 20),
+            ..Default::default()
+        };
+        assert!(injection
+            .inject(&mut module, FUNCTION_NAME, &options)
+            .is_ok());
+
+        let hashing_index = module
+            .get_imported_function_index("ext_hashing_blake2_256_version_1")
+            .unwrap() as u32;
+        let free_index = module.get_free_index().unwrap() as u32;
+        let code = get_function_body(&mut module).code().elements();
+
+        let call = vec![
+            Instruction::GetLocal(pointer_local),
+            Instruction::I64ExtendUI32,
+            Instruction::I64Const((1 << 20) << 32),
+            Instruction::I64Or,
+            Instruction::Call(hashing_index),
+            Instruction::Call(free_index),
+        ];
+        assert!(code.windows(call.len()).any(|window| window == call));
+    }
+
+    #[test]
+    fn test_inject_host_cpu_burn_sr25519_verify() {
+        let mut module = load_module();
+        let pointer_local = module.get_next_local_index(FUNCTION_NAME).unwrap() as u32;
+
+        let injection = Injection::HostCpuBurn;
+        let options = InjectionOptions {
+            host_cpu_burn: Some(HostCpuBurn::Sr25519Verify),
+            iterations: Some(1000),
+            buffer_size: Some(1024),
+            ..Default::default()
+        };
+        assert!(injection
+            .inject(&mut module, FUNCTION_NAME, &options)
+            .is_ok());
+
+        let code = get_function_body(&mut module).code().elements();
+
+        // The signature and the public key are written right after the message
+        let stores = SR25519_SIGNATURE
+            .iter()
+            .chain(SR25519_PUBLIC_KEY.iter())
+            .enumerate()
+            .flat_map(|(offset, byte)| {
+                [
+                    Instruction::GetLocal(pointer_local),
+                    Instruction::I32Const(*byte as i32),
+                    Instruction::I32Store8(0, 1024 + offset as u32),
+                ]
+            })
+            .collect::<Vec<_>>();
+        assert!(code.windows(stores.len()).any(|window| window == stores));
+        assert!(stores.contains(&Instruction::I32Const(0x80)));
+    }
+
+    #[test]
+    fn test_inject_host_cpu_burn_trie_root() {
+        let mut module = load_module();
+        let pointer_local = module.get_next_local_index(FUNCTION_NAME).unwrap() as u32;
+
+        let injection = Injection::HostCpuBurn;
+        let options = InjectionOptions {
+            host_cpu_burn: Some(HostCpuBurn::TrieRoot),
+            iterations: Some(1000),
+            buffer_size: Some(1024),
+            ..Default::default()
+        };
+        assert!(injection
+            .inject(&mut module, FUNCTION_NAME, &options)
+            .is_ok());
+
+        // The trie root function isn't imported by the test wasm
+        let trie_root_index = module
+            .get_imported_function_index("ext_trie_blake2_256_root_version_1")
+            .unwrap() as u32;
+        let code = get_function_body(&mut module).code().elements();
+
+        let call = vec![
+            Instruction::GetLocal(pointer_local),
+            Instruction::I64ExtendUI32,
+            // Compact encoded 1, 0 and 1024 followed by 1024 bytes
+            Instruction::I64Const((4 + 1024) << 32),
+            Instruction::I64Or,
+            Instruction::Call(trie_root_index),
+        ];
+        assert!(code.windows(call.len()).any(|window| window == call));
+    }
+
+    #[test]
+    fn test_inject_forbidden_host_call() {
+        let mut module = load_module();
+        let import_section_len = module.get_import_section_len().unwrap();
+
+        let injection = Injection::ForbiddenHostCall;
+        let options = InjectionOptions {
+            forbidden_host_function: Some(ForbiddenHostFunction::Timestamp),
+            ..Default::default()
+        };
+        assert!(injection
+            .inject(&mut module, FUNCTION_NAME, &options)
+            .is_ok());
+
+        // The timestamp function isn't imported by the test wasm
+        assert_eq!(
+            module
+                .get_imported_function_index("ext_offchain_timestamp_version_1")
+                .unwrap(),
+            import_section_len
+        );
+        let code = get_function_body(&mut module).code().elements();
+        assert!(code.starts_with(&[
+            Instruction::Call(import_section_len as u32),
+            Instruction::Drop,
+        ]));
+    }
+
+    #[test]
+    fn test_inject_forbidden_host_call_http_request_start() {
+        let mut module = load_module();
+        let import_section_len = module.get_import_section_len().unwrap();
+        let pointer_local = module.get_next_local_index(FUNCTION_NAME).unwrap() as u32;
+
+        let injection = Injection::ForbiddenHostCall;
+        let options = InjectionOptions {
+            forbidden_host_function: Some(ForbiddenHostFunction::HttpRequestStart),
+            ..Default::default()
+        };
+        assert!(injection
+            .inject(&mut module, FUNCTION_NAME, &options)
+            .is_ok());
+
+        // The http request function isn't imported by the test wasm
+        assert_eq!(
+            module
+                .get_imported_function_index("ext_offchain_http_request_start_version_1")
+                .unwrap(),
+            import_section_len
+        );
+        let malloc_index = module.get_malloc_index().unwrap() as u32;
+        let function_body = get_function_body(&mut module);
+        assert_eq!(
+            function_body.locals().last(),
+            Some(&Local::new(1, ValueType::I32))
+        );
+
+        // "GET", "http://localhost" and an empty list of headers, passed as three spans
+        let span = |offset: i32, length: i64| {
+            [
+                Instruction::GetLocal(pointer_local),
+                Instruction::I32Const(offset),
+                Instruction::I32Add,
+                Instruction::I64ExtendUI32,
+                Instruction::I64Const(length << 32),
+                Instruction::I64Or,
+            ]
+        };
+        let expected = [
+            vec![
+                Instruction::I32Const(20),
+                Instruction::Call(malloc_index),
+                Instruction::SetLocal(pointer_local),
+            ],
+            write_bytes(b"GEThttp://localhost\0", pointer_local, 0),
+            span(0, 3).to_vec(),
+            span(3, 16).to_vec(),
+            span(19, 1).to_vec(),
+            vec![
+                Instruction::Call(import_section_len as u32),
+                Instruction::Drop,
+            ],
+        ]
+        .concat();
+        assert!(function_body.code().elements().starts_with(&expected));
+    }
+
+    #[test]
+    fn test_inject_runtime_panic() {
+        let mut module = load_module();
+        let pointer_local = module.get_next_local_index(FUNCTION_NAME).unwrap() as u32;
+        let panic_index = module
+            .get_named_function_index("core::panicking::panic")
+            .unwrap() as u32;
+
+        let injection = Injection::RuntimePanic;
+        let options = InjectionOptions {
+            panic_message: Some("Injected panic".to_string()),
+            ..Default::default()
+        };
+        assert!(injection
+            .inject(&mut module, FUNCTION_NAME, &options)
+            .is_ok());
+
+        let code = get_function_body(&mut module).code().elements();
+
+        // Location { file: &str, line: u32, col: u32 }, right after the message and the file
+        let location = vec![
+            Instruction::GetLocal(pointer_local),
+            Instruction::GetLocal(pointer_local),
+            Instruction::I32Const(14),
+            Instruction::I32Add,
+            Instruction::I32Store(2, 28),
+            Instruction::GetLocal(pointer_local),
+            Instruction::I32Const(13),
+            Instruction::I32Store(2, 32),
+            Instruction::GetLocal(pointer_local),
+            Instruction::I32Const(1),
+            Instruction::I32Store(2, 36),
+            Instruction::GetLocal(pointer_local),
+            Instruction::I32Const(1),
+            Instruction::I32Store(2, 40),
+        ];
+        assert!(code
+            .windows(location.len())
+            .any(|window| window == location));
+
+        let call = vec![
+            Instruction::GetLocal(pointer_local),
+            Instruction::I32Const(14),
+            Instruction::GetLocal(pointer_local),
+            // 14 bytes of message and 13 bytes of file, aligned to 4 bytes
+            Instruction::I32Const(28),
+            Instruction::I32Add,
+            Instruction::Call(panic_index),
+        ];
+        assert!(code.windows(call.len()).any(|window| window == call));
+    }
+
+    #[test]
+    fn test_inject_runtime_panic_without_names() {
+        let mut module = module_from_wat(
+            r#"
+            (module
+                (import "env" "ext_allocator_malloc_version_1" (func (param i32) (result i32)))
+                (import "env" "memory" (memory 1))
+                (func (export "validate_block") (param i32 i32) (result i64)
+                    i64.const 0)
+            )
+            "#,
+        );
+
+        let injection = Injection::RuntimePanic;
+        let options = InjectionOptions {
+            panic_message: Some("Injected panic".to_string()),
+            ..Default::default()
+        };
+        assert!(injection
+            .inject(&mut module, FUNCTION_NAME, &options)
+            .is_err());
+    }
+
+    #[test]
+    fn test_inject_stack_pointer_corruption() {
+        let mut module = load_module();
+        let stack_pointer_index = module.get_global_index("__stack_pointer").unwrap() as u32;
+        let data_end_index = module.get_global_index("__data_end").unwrap() as u32;
+
+        let injection = Injection::StackPointerCorruption;
+        let options = InjectionOptions {
+            stack_pointer_corruption: Some(StackPointerCorruption::BelowDataEnd),
+            ..Default::default()
+        };
+        assert!(injection
+            .inject(&mut module, FUNCTION_NAME, &options)
+            .is_ok());
+
+        let code = get_function_body(&mut module).code().elements();
+        assert!(code.starts_with(&[
+            Instruction::GetGlobal(data_end_index),
+            Instruction::I32Const(1),
+            Instruction::I32Sub,
+            Instruction::I32Const(-16),
+            Instruction::I32And,
+            Instruction::SetGlobal(stack_pointer_index),
+        ]));
+    }
+
+    #[test]
+    fn test_inject_stack_pointer_corruption_past_memory() {
+        let mut module = load_module();
+        let stack_pointer_index = module.get_global_index("__stack_pointer").unwrap() as u32;
+
+        let injection = Injection::StackPointerCorruption;
+        let options = InjectionOptions {
+            stack_pointer_corruption: Some(StackPointerCorruption::PastMemory),
+            ..Default::default()
+        };
+        assert!(injection
+            .inject(&mut module, FUNCTION_NAME, &options)
+            .is_ok());
+
+        let code = get_function_body(&mut module).code().elements();
+        assert!(code.starts_with(&[
+            Instruction::I32Const(-16),
+            Instruction::SetGlobal(stack_pointer_index),
+        ]));
+    }
+
+    #[test]
+    fn test_inject_stack_pointer_corruption_without_stack_pointer() {
+        let mut module = module_from_wat(
+            r#"
+            (module
+                (global (mut i32) (i32.const 1048576))
+                (func (export "validate_block") (param i32 i32) (result i64)
+                    i64.const 0)
+            )
+            "#,
+        );
+
+        let injection = Injection::StackPointerCorruption;
+        let options = InjectionOptions {
+            stack_pointer_corruption: Some(StackPointerCorruption::Zero),
+            ..Default::default()
+        };
+        assert!(injection
+            .inject(&mut module, FUNCTION_NAME, &options)
+            .is_err());
+    }
+
+    #[test]
+    fn test_inject_out_of_bounds_access() {
+        let mut module = load_module();
+
+        // The offset overflows u32 together with the address
+        let injection = Injection::OutOfBoundsAccess;
+        let options = InjectionOptions {
+            address: Some(u32::MAX),
+            offset: Some(1),
+            width: Some(AccessWidth::Bits64),
+            access: Some(MemoryAccess::Store),
+            alignment: Some(0),
+            ..Default::default()
+        };
+        assert!(injection
+            .inject(&mut module, FUNCTION_NAME, &options)
+            .is_ok());
+
+        let code = get_function_body(&mut module).code().elements();
+        assert!(code.starts_with(&[
+            Instruction::I32Const(-1),
+            Instruction::I64Const(-1),
+            Instruction::I64Store(0, 1),
+        ]));
+    }
+
+    #[test]
+    fn test_inject_out_of_bounds_access_overaligned() {
+        let mut module = load_module();
+
+        let injection = Injection::OutOfBoundsAccess;
+        let options = InjectionOptions {
+            address: Some(0),
+            width: Some(AccessWidth::Bits16),
+            access: Some(MemoryAccess::Load),
+            alignment: Some(2),
+            ..Default::default()
+        };
+        assert!(injection
+            .inject(&mut module, FUNCTION_NAME, &options)
+            .is_err());
     }
 }
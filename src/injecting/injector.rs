@@ -1,4 +1,17 @@
-use wasm_instrument::parity_wasm::elements::{FuncBody, ImportSection, Internal::Function, Module};
+use wasm_instrument::parity_wasm::elements::{
+    Deserialize, ExportEntry, ExportSection, External, Func, FuncBody, FunctionType, GlobalEntry,
+    GlobalType, ImportCountType, ImportEntry, ImportSection, IndexMap, InitExpr, Instruction,
+    Internal::{self, Function, Global},
+    MemorySection, MemoryType, Module, ResizableLimits, Section, Serialize, Type, ValueType,
+    VarUint32, VarUint7,
+};
+
+/// Id of the subsection of the `name` custom section which names the functions.
+pub const FUNCTION_NAMES_SUBSECTION_ID: u8 = 1;
+/// Id of the subsection of the `name` custom section which names the locals of the functions.
+pub const LOCAL_NAMES_SUBSECTION_ID: u8 = 2;
+/// Id of the subsection of the `name` custom section which names the globals.
+pub const GLOBAL_NAMES_SUBSECTION_ID: u8 = 7;
 
 /// # This trait extends the module with helper functions used for injecting code into the module.
 pub trait FunctionMapper {
@@ -27,6 +40,24 @@ pub trait FunctionMapper {
         function_name: &str,
     ) -> Result<&mut FuncBody, String>;
     fn get_malloc_index(&mut self) -> Result<usize, String>;
+    fn get_free_index(&mut self) -> Result<usize, String>;
+    fn get_imported_function_index(&mut self, function_name: &str) -> Result<usize, String>;
+    fn get_function_type(&mut self, global_function_index: usize) -> Result<FunctionType, String>;
+    fn get_or_add_type_index(&mut self, function_type: FunctionType) -> Result<usize, String>;
+    fn get_next_local_index(&mut self, function_name: &str) -> Result<usize, String>;
+    fn get_name_map(&mut self, subsection_id: u8) -> Result<IndexMap<String>, String>;
+    fn get_global_index(&mut self, global_name: &str) -> Result<usize, String>;
+    fn get_named_function_index(&mut self, function_name: &str) -> Result<usize, String>;
+    fn add_function(
+        &mut self,
+        function_type: FunctionType,
+        function_body: FuncBody,
+    ) -> Result<usize, String>;
+    fn get_or_add_imported_function(
+        &mut self,
+        function_name: &str,
+        function_type: FunctionType,
+    ) -> Result<usize, String>;
 }
 
 impl FunctionMapper for Module {
@@ -91,23 +122,283 @@ impl FunctionMapper for Module {
         Ok(function_body)
     }
 
-    /// # Takes a module and returns the index of the malloc function in the import section.
+    /// # Takes a module and returns the global function index of the imported malloc function.
     fn get_malloc_index(&mut self) -> Result<usize, String> {
-        let import_section = self.import_section_mut().ok_or("No table section")?;
-        let malloc_index = import_section
+        self.get_imported_function_index("ext_allocator_malloc")
+    }
+
+    /// # Takes a module and returns the global function index of the imported free function.
+    fn get_free_index(&mut self) -> Result<usize, String> {
+        self.get_imported_function_index("ext_allocator_free")
+    }
+
+    /// # Takes a module and a global function index and returns the type of the function.
+    /// The function can be both imported and local.
+    ///
+    /// # Errors
+    /// - Returns an error if the type section is not found.
+    /// - Returns an error if the function or its type is not found.
+    fn get_function_type(&mut self, global_function_index: usize) -> Result<FunctionType, String> {
+        let import_section_len = self.get_import_section_len()?;
+
+        let type_index = if global_function_index < import_section_len {
+            // Imported functions come first in the function index space
+            self.import_section()
+                .ok_or("No import section")?
+                .entries()
+                .iter()
+                .filter_map(|entry| match entry.external() {
+                    External::Function(type_index) => Some(*type_index),
+                    _ => None,
+                })
+                .nth(global_function_index)
+        } else {
+            self.function_section()
+                .ok_or("No function section")?
+                .entries()
+                .get(global_function_index - import_section_len)
+                .map(Func::type_ref)
+        }
+        .ok_or(format!("Function {} not found", global_function_index))?;
+
+        let Type::Function(function_type) = self
+            .type_section()
+            .ok_or("No type section")?
+            .types()
+            .get(type_index as usize)
+            .ok_or(format!("Type {} not found in the type section", type_index))?;
+
+        Ok(function_type.clone())
+    }
+
+    /// # Takes a module and a function type and returns the index of the type in the type section.
+    /// The type is appended to the type section if it is not there yet.
+    ///
+    /// # Errors
+    /// - Returns an error if the type section is not found.
+    fn get_or_add_type_index(&mut self, function_type: FunctionType) -> Result<usize, String> {
+        let types = self
+            .type_section_mut()
+            .ok_or("No type section")?
+            .types_mut();
+
+        let type_index = match types
+            .iter()
+            .position(|Type::Function(existing_type)| *existing_type == function_type)
+        {
+            Some(type_index) => type_index,
+            None => {
+                types.push(Type::Function(function_type));
+                types.len() - 1
+            }
+        };
+
+        Ok(type_index)
+    }
+
+    /// # Takes a module and a function name and returns the index of the next local added to the function.
+    /// Locals are indexed after the parameters of the function.
+    fn get_next_local_index(&mut self, function_name: &str) -> Result<usize, String> {
+        let global_function_index = self.get_global_function_index(function_name)?;
+        let params_count = self
+            .get_function_type(global_function_index)?
+            .params()
+            .len();
+
+        let local_function_index = global_function_index - self.get_import_section_len()?;
+        let locals_count = self
+            .get_function_body(local_function_index, function_name)?
+            .locals()
+            .iter()
+            .map(|local| local.count() as usize)
+            .sum::<usize>();
+
+        Ok(params_count + locals_count)
+    }
+
+    /// # Takes a module and a subsection id and returns the name map of the subsection in the `name` custom section.
+    /// The section is read raw, so that subsections unknown to `parity_wasm` (e.g. 7 for globals) can be used.
+    ///
+    /// # Errors
+    /// - Returns an error if the `name` custom section or the subsection is not found.
+    /// - Returns an error if the subsection could not be deserialized.
+    fn get_name_map(&mut self, subsection_id: u8) -> Result<IndexMap<String>, String> {
+        let payload = self
+            .custom_sections()
+            .find(|section| section.name() == "name")
+            .ok_or("No name section")?
+            .payload();
+
+        let mut reader = payload;
+        while !reader.is_empty() {
+            let id: u8 = VarUint7::deserialize(&mut reader)
+                .map_err(|err| format!("Could not read name subsection id: {}", err))?
+                .into();
+            let size: usize = u32::from(
+                VarUint32::deserialize(&mut reader)
+                    .map_err(|err| format!("Could not read name subsection size: {}", err))?,
+            ) as usize;
+
+            let (mut subsection, rest) = reader
+                .split_at_checked(size)
+                .ok_or("Name subsection is out of bounds")?;
+            if id == subsection_id {
+                return IndexMap::deserialize(size, &mut subsection)
+                    .map_err(|err| format!("Could not read name map: {}", err));
+            }
+
+            reader = rest;
+        }
+
+        Err(format!(
+            "No subsection {} in the name section",
+            subsection_id
+        ))
+    }
+
+    /// # Takes a module and a global name and returns the index of the global.
+    /// The global is looked up in the export section first and then in the `name` custom section.
+    ///
+    /// # Errors
+    /// - Returns an error if the global is neither exported nor named.
+    fn get_global_index(&mut self, global_name: &str) -> Result<usize, String> {
+        let exported_global_index = self.export_section().and_then(|export_section| {
+            export_section
+                .entries()
+                .iter()
+                .find_map(|export| match export.internal() {
+                    Global(index) if export.field() == global_name => Some(*index),
+                    _ => None,
+                })
+        });
+
+        let global_index = match exported_global_index {
+            Some(global_index) => global_index,
+            None => self
+                .get_name_map(GLOBAL_NAMES_SUBSECTION_ID)
+                .ok()
+                .and_then(|global_names| {
+                    global_names
+                        .iter()
+                        .find_map(|(index, name)| (name == global_name).then_some(index))
+                })
+                .ok_or(format!("Global '{}' not found", global_name))?,
+        };
+
+        Ok(global_index as usize)
+    }
+
+    /// # Takes a module and a function name and returns the global function index of the function named
+    /// in the `name` custom section. Mangled names match their demangled path without the hash,
+    /// e.g. `core::panicking::panic` matches `_ZN4core9panicking5panic17hea78c4e8b318407dE`.
+    ///
+    /// # Errors
+    /// - Returns an error if the function is not named.
+    fn get_named_function_index(&mut self, function_name: &str) -> Result<usize, String> {
+        let function_index = self
+            .get_name_map(FUNCTION_NAMES_SUBSECTION_ID)?
+            .iter()
+            .find_map(|(index, name)| {
+                (name == function_name || demangle(name).is_some_and(|path| path == function_name))
+                    .then_some(index)
+            })
+            .ok_or(format!(
+                "Function '{}' not found in the name section",
+                function_name
+            ))?;
+
+        Ok(function_index as usize)
+    }
+
+    /// # Takes a module, a function type and a function body, appends the function to the module
+    /// and returns its global function index.
+    /// The function type is reused if the type section already contains it.
+    ///
+    /// # Errors
+    /// - Returns an error if the type, function or code section is not found.
+    fn add_function(
+        &mut self,
+        function_type: FunctionType,
+        function_body: FuncBody,
+    ) -> Result<usize, String> {
+        let type_index = self.get_or_add_type_index(function_type)?;
+
+        // Declare the function ...
+        let functions = self
+            .function_section_mut()
+            .ok_or("No function section")?
+            .entries_mut();
+        functions.push(Func::new(type_index as u32));
+        let local_function_index = functions.len() - 1;
+
+        // ... and define its body
+        self.code_section_mut()
+            .ok_or("No code section")?
+            .bodies_mut()
+            .push(function_body);
+
+        Ok(self.get_import_section_len()? + local_function_index)
+    }
+
+    /// # Takes a module and the name of an imported function and returns its global function index.
+    /// The version suffix of the name can be omitted, e.g. `ext_allocator_free` resolves `ext_allocator_free_version_1`.
+    ///
+    /// # Errors
+    /// - Returns an error if the function is not imported.
+    fn get_imported_function_index(&mut self, function_name: &str) -> Result<usize, String> {
+        let imported_function_index = self
+            .import_section()
+            .ok_or("No import section")?
             .entries()
             .iter()
-            .enumerate()
-            .find_map(|(index, entry)| {
-                if entry.field().starts_with("ext_allocator_malloc") {
-                    Some(index)
-                } else {
-                    None
-                }
+            // Only functions count in the function index space
+            .filter(|entry| matches!(entry.external(), External::Function(_)))
+            .position(|entry| {
+                entry.field() == function_name
+                    || entry
+                        .field()
+                        .strip_prefix(function_name)
+                        .is_some_and(|suffix| suffix.starts_with("_version_"))
             })
-            .ok_or("No malloc function in table section")?;
+            .ok_or(format!(
+                "Function '{}' not found in the import section",
+                function_name
+            ))?;
 
-        Ok(malloc_index)
+        Ok(imported_function_index)
+    }
+
+    /// # Takes a module, the name of a function imported from `env` and its type and returns its global function index.
+    /// The function is imported if it is not imported yet. As imported functions come first in the function
+    /// index space, all references to the following functions are shifted.
+    ///
+    /// # Errors
+    /// - Returns an error if the import or type section is not found.
+    /// - Returns an error if the `name` custom section could not be updated.
+    fn get_or_add_imported_function(
+        &mut self,
+        function_name: &str,
+        function_type: FunctionType,
+    ) -> Result<usize, String> {
+        if let Ok(imported_function_index) = self.get_imported_function_index(function_name) {
+            return Ok(imported_function_index);
+        }
+
+        let type_index = self.get_or_add_type_index(function_type)?;
+        let imported_function_index = self.get_import_section_len()?;
+
+        self.import_section_mut()
+            .ok_or("No import section")?
+            .entries_mut()
+            .push(ImportEntry::new(
+                "env".to_string(),
+                function_name.to_string(),
+                External::Function(type_index as u32),
+            ));
+
+        shift_function_indices(self, imported_function_index as u32)?;
+
+        Ok(imported_function_index)
     }
 
     /// # Takes a module, a function name and a body mapper function and maps over the function body.
@@ -141,23 +432,662 @@ impl FunctionMapper for Module {
     }
 }
 
+/// # Memory declaration enum
+///
+/// Selects how the memory of the module is declared.
+#[derive(clap::ValueEnum, PartialEq, Eq, Clone, Copy, Debug)]
+pub enum MemoryDeclaration {
+    /// Memory imported from `env.memory`
+    Imported,
+    /// Memory defined in the memory section and exported as `memory`
+    Exported,
+}
+
+/// # Heap base tampering enum
+///
+/// Selects how the exported `__heap_base` global, where the host allocator starts, is tampered with.
+#[derive(clap::ValueEnum, PartialEq, Eq, Clone, Copy, Debug)]
+pub enum HeapBaseTampering {
+    /// 8-byte aligned value right below `__data_end`, so that allocations overwrite the static data
+    BelowDataEnd,
+    /// 8-byte aligned value in the middle of the stack, below the initial `__stack_pointer`
+    InsideStack,
+    /// Value which is not 8-byte aligned
+    Unaligned,
+    /// First address past the initial memory, which has to be smaller than 4 GiB
+    PastMemory,
+    /// `__heap_base` is not exported at all
+    RemoveExport,
+}
+
+/// # This trait extends the module with helper functions used for mutating the declarations of the module.
+pub trait ModuleMapper {
+    fn get_memory_type(&mut self) -> Result<MemoryType, String>;
+    fn get_memory_limits(&mut self) -> Result<ResizableLimits, String>;
+    fn set_memory_limits(
+        &mut self,
+        initial_pages: u32,
+        maximum_pages: Option<u32>,
+    ) -> Result<(), String>;
+    fn convert_memory(&mut self, declaration: MemoryDeclaration) -> Result<(), String>;
+    fn get_global_entry(&mut self, global_index: usize) -> Result<&mut GlobalEntry, String>;
+    fn get_global_i32_value(&mut self, global_name: &str) -> Result<i32, String>;
+    fn tamper_heap_base(&mut self, tampering: HeapBaseTampering) -> Result<(), String>;
+    fn resolve_global_index(&mut self, global: &str) -> Result<usize, String>;
+    fn get_global_type(&mut self, global_index: usize) -> Result<GlobalType, String>;
+    fn set_global_init(&mut self, global_index: usize, init_expr: InitExpr) -> Result<(), String>;
+    fn set_global_value(&mut self, global_index: usize, value: &str) -> Result<(), String>;
+    fn set_global_mutability(
+        &mut self,
+        global_index: usize,
+        is_mutable: bool,
+    ) -> Result<(), String>;
+}
+
+impl ModuleMapper for Module {
+    /// # Takes a module and returns the type of its memory.
+    /// The memory can be both imported and defined in the memory section.
+    ///
+    /// # Errors
+    /// - Returns an error if the module has no memory.
+    fn get_memory_type(&mut self) -> Result<MemoryType, String> {
+        let imported_memory = self.import_section().and_then(|import_section| {
+            import_section
+                .entries()
+                .iter()
+                .find_map(|entry| match entry.external() {
+                    External::Memory(memory_type) => Some(memory_type),
+                    _ => None,
+                })
+        });
+        let memory_type = imported_memory
+            .or_else(|| {
+                self.memory_section()
+                    .and_then(|memory_section| memory_section.entries().first())
+            })
+            .ok_or("No memory in the module")?;
+
+        Ok(*memory_type)
+    }
+
+    /// # Takes a module and returns the limits of its memory, in pages.
+    /// The memory can be both imported and defined in the memory section.
+    ///
+    /// # Errors
+    /// - Returns an error if the module has no memory.
+    fn get_memory_limits(&mut self) -> Result<ResizableLimits, String> {
+        Ok(*self.get_memory_type()?.limits())
+    }
+
+    /// # Takes a module and the initial and maximum pages and rewrites the limits of its memory.
+    /// The memory can be both imported and defined in the memory section. The limits are not validated,
+    /// so that the maximum can be below the initial pages and the initial pages can exceed the 4 GiB limit.
+    /// A shared memory stays shared.
+    ///
+    /// # Errors
+    /// - Returns an error if the module has no memory.
+    /// - Returns an error if the maximum of a shared memory is removed, as shared memories need one.
+    fn set_memory_limits(
+        &mut self,
+        initial_pages: u32,
+        maximum_pages: Option<u32>,
+    ) -> Result<(), String> {
+        let imported_memory = self.import_section_mut().and_then(|import_section| {
+            import_section
+                .entries_mut()
+                .iter_mut()
+                .find_map(|entry| match entry.external_mut() {
+                    External::Memory(memory_type) => Some(memory_type),
+                    _ => None,
+                })
+        });
+        if let Some(memory_type) = imported_memory {
+            *memory_type = memory_type_with_limits(memory_type, initial_pages, maximum_pages)?;
+            return Ok(());
+        }
+
+        let memory_type = self
+            .memory_section_mut()
+            .and_then(|memory_section| memory_section.entries_mut().first_mut())
+            .ok_or("No memory in the module")?;
+        *memory_type = memory_type_with_limits(memory_type, initial_pages, maximum_pages)?;
+
+        Ok(())
+    }
+
+    /// # Takes a module and converts its memory to the given declaration.
+    /// The limits and the shared flag of the memory are preserved, as are the data segments, which keep referring
+    /// to memory 0.
+    /// Nothing is changed if the memory is already declared as given.
+    ///
+    /// # Errors
+    /// - Returns an error if the module has no memory.
+    /// - Returns an error if the module already exports something else as `memory`.
+    /// - Returns an error if the module both imports and defines a memory.
+    ///
+    /// The module is left unchanged when an error is returned.
+    fn convert_memory(&mut self, declaration: MemoryDeclaration) -> Result<(), String> {
+        let memory_type = self.get_memory_type()?;
+
+        match declaration {
+            MemoryDeclaration::Exported => {
+                let Some(position) = self.import_section().and_then(|import_section| {
+                    import_section
+                        .entries()
+                        .iter()
+                        .position(|entry| matches!(entry.external(), External::Memory(_)))
+                }) else {
+                    return Ok(());
+                };
+                // Everything is checked before the module is changed, so that it's left untouched on errors
+                if self.export_section().is_some_and(|export_section| {
+                    export_section
+                        .entries()
+                        .iter()
+                        .any(|export| export.field() == "memory")
+                }) {
+                    return Err("The module already exports `memory`".to_string());
+                }
+                if self.memory_section().is_some() {
+                    return Err("The module already has a memory section".to_string());
+                }
+
+                // Memories don't count in the function index space, so no function is shifted
+                if let Some(import_section) = self.import_section_mut() {
+                    import_section.entries_mut().remove(position);
+                }
+
+                self.insert_section(Section::Memory(MemorySection::with_entries(vec![
+                    memory_type,
+                ])))
+                .map_err(|err| format!("Could not add the memory section: {}", err))?;
+
+                let memory_export = ExportEntry::new("memory".to_string(), Internal::Memory(0));
+                match self.export_section_mut() {
+                    Some(export_section) => export_section.entries_mut().push(memory_export),
+                    None => self
+                        .insert_section(Section::Export(ExportSection::with_entries(vec![
+                            memory_export,
+                        ])))
+                        .map_err(|err| format!("Could not add the export section: {}", err))?,
+                }
+            }
+            MemoryDeclaration::Imported => {
+                if self.memory_section().is_none() {
+                    return Ok(());
+                }
+                self.sections_mut()
+                    .retain(|section| !matches!(section, Section::Memory(_)));
+
+                if let Some(export_section) = self.export_section_mut() {
+                    export_section
+                        .entries_mut()
+                        .retain(|export| !matches!(export.internal(), Internal::Memory(_)));
+                }
+
+                let memory_import = ImportEntry::new(
+                    "env".to_string(),
+                    "memory".to_string(),
+                    External::Memory(memory_type),
+                );
+                match self.import_section_mut() {
+                    Some(import_section) => import_section.entries_mut().push(memory_import),
+                    None => self
+                        .insert_section(Section::Import(ImportSection::with_entries(vec![
+                            memory_import,
+                        ])))
+                        .map_err(|err| format!("Could not add the import section: {}", err))?,
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// # Takes a module and a global index and returns the entry of the global in the global section.
+    ///
+    /// # Errors
+    /// - Returns an error if the global is imported, as imported globals have no entry.
+    /// - Returns an error if the global is not in the global section.
+    fn get_global_entry(&mut self, global_index: usize) -> Result<&mut GlobalEntry, String> {
+        // Imported globals come first in the global index space
+        let imported_globals_count = self.import_count(ImportCountType::Global);
+        if global_index < imported_globals_count {
+            return Err(format!("Global {} is imported", global_index));
+        }
+
+        self.global_section_mut()
+            .ok_or("No global section")?
+            .entries_mut()
+            .get_mut(global_index - imported_globals_count)
+            .ok_or(format!(
+                "Global {} not found in the global section",
+                global_index
+            ))
+    }
+
+    /// # Takes a module and a global name and returns the value of the global initialized with an `i32.const`.
+    ///
+    /// # Errors
+    /// - Returns an error if the global is not found.
+    /// - Returns an error if the global is not initialized with an `i32.const`.
+    fn get_global_i32_value(&mut self, global_name: &str) -> Result<i32, String> {
+        let global_index = self.get_global_index(global_name)?;
+
+        match self.get_global_entry(global_index)?.init_expr().code() {
+            [Instruction::I32Const(value), Instruction::End] => Ok(*value),
+            _ => Err(format!(
+                "Global '{}' is not initialized with an i32 constant",
+                global_name
+            )),
+        }
+    }
+
+    /// # Takes a module and tampers with the init expression or the export of the `__heap_base` global.
+    ///
+    /// # Errors
+    /// - Returns an error if `__heap_base`, or the global the tampered value is derived from, is not found.
+    /// - Returns an error if no address past the initial memory can be represented, i.e. for 65536 pages or more.
+    fn tamper_heap_base(&mut self, tampering: HeapBaseTampering) -> Result<(), String> {
+        if tampering == HeapBaseTampering::RemoveExport {
+            let export_section = self.export_section_mut().ok_or("No export section")?;
+            let exports_count = export_section.entries().len();
+            export_section.entries_mut().retain(|export| {
+                !(export.field() == "__heap_base" && matches!(export.internal(), Global(_)))
+            });
+            if export_section.entries().len() == exports_count {
+                return Err("Global '__heap_base' is not exported".to_string());
+            }
+
+            return Ok(());
+        }
+
+        let heap_base = self.get_global_i32_value("__heap_base")? as u32;
+        let tampered_heap_base = match tampering {
+            HeapBaseTampering::BelowDataEnd => {
+                (self.get_global_i32_value("__data_end")? as u32).saturating_sub(1) & !7
+            }
+            // The stack grows down from the initial stack pointer
+            HeapBaseTampering::InsideStack => {
+                (self.get_global_i32_value("__stack_pointer")? as u32 / 2) & !7
+            }
+            HeapBaseTampering::Unaligned => heap_base | 1,
+            HeapBaseTampering::PastMemory => {
+                let initial_pages = self.get_memory_limits()?.initial() as u64;
+                u32::try_from(initial_pages * 65536)
+                    .map_err(|_| "No address past the initial memory fits in an i32")?
+            }
+            HeapBaseTampering::RemoveExport => heap_base,
+        };
+
+        let heap_base_index = self.get_global_index("__heap_base")?;
+        *self.get_global_entry(heap_base_index)?.init_expr_mut() = InitExpr::new(vec![
+            Instruction::I32Const(tampered_heap_base as i32),
+            Instruction::End,
+        ]);
+
+        Ok(())
+    }
+
+    /// # Takes a module and a global, given by its index, export name or name in the `name` custom section,
+    /// and returns the index of the global.
+    ///
+    /// # Errors
+    /// - Returns an error if the global is not found.
+    fn resolve_global_index(&mut self, global: &str) -> Result<usize, String> {
+        match global.parse::<usize>() {
+            Ok(global_index) if global_index < self.globals_space() => Ok(global_index),
+            Ok(global_index) => Err(format!("Global {} not found", global_index)),
+            Err(_) => self.get_global_index(global),
+        }
+    }
+
+    /// # Takes a module and a global index and returns the type of the global.
+    /// The global can be both imported and defined in the global section.
+    ///
+    /// # Errors
+    /// - Returns an error if the global is not found.
+    fn get_global_type(&mut self, global_index: usize) -> Result<GlobalType, String> {
+        let imported_global_type = self.import_section().and_then(|import_section| {
+            import_section
+                .entries()
+                .iter()
+                .filter_map(|entry| match entry.external() {
+                    External::Global(global_type) => Some(*global_type),
+                    _ => None,
+                })
+                .nth(global_index)
+        });
+
+        match imported_global_type {
+            Some(global_type) => Ok(global_type),
+            None => Ok(*self.get_global_entry(global_index)?.global_type()),
+        }
+    }
+
+    /// # Takes a module, a global index and an init expression and replaces the init expression of the global.
+    /// The init expression has to be a constant expression of the type of the global: a constant
+    /// or the value of an immutable imported global.
+    ///
+    /// # Errors
+    /// - Returns an error if the global is imported or not found.
+    /// - Returns an error if the init expression is not a constant expression of the type of the global.
+    fn set_global_init(&mut self, global_index: usize, init_expr: InitExpr) -> Result<(), String> {
+        let content_type = self.get_global_type(global_index)?.content_type();
+
+        let init_type = match init_expr.code() {
+            [Instruction::I32Const(_), Instruction::End] => ValueType::I32,
+            [Instruction::I64Const(_), Instruction::End] => ValueType::I64,
+            [Instruction::F32Const(_), Instruction::End] => ValueType::F32,
+            [Instruction::F64Const(_), Instruction::End] => ValueType::F64,
+            [Instruction::GetGlobal(index), Instruction::End]
+                if (*index as usize) < self.import_count(ImportCountType::Global) =>
+            {
+                let global_type = self.get_global_type(*index as usize)?;
+                if global_type.is_mutable() {
+                    return Err(format!(
+                        "Global {} is mutable, so it can't initialize global {}",
+                        index, global_index
+                    ));
+                }
+                global_type.content_type()
+            }
+            _ => {
+                return Err(format!(
+                    "Init expression of global {} is not a constant expression",
+                    global_index
+                ))
+            }
+        };
+        if init_type != content_type {
+            return Err(format!(
+                "Init expression of type {} doesn't match global {} of type {}",
+                init_type, global_index, content_type
+            ));
+        }
+
+        *self.get_global_entry(global_index)?.init_expr_mut() = init_expr;
+
+        Ok(())
+    }
+
+    /// # Takes a module, a global index and a value and initializes the global with the value as a constant.
+    /// The value is parsed according to the type of the global, e.g. `-1` or `4294967295` for an i32.
+    ///
+    /// # Errors
+    /// - Returns an error if the global is imported or not found.
+    /// - Returns an error if the value can't be parsed as the type of the global.
+    fn set_global_value(&mut self, global_index: usize, value: &str) -> Result<(), String> {
+        let content_type = self.get_global_type(global_index)?.content_type();
+        let constant = match content_type {
+            ValueType::I32 => value
+                .parse::<i32>()
+                .or_else(|_| value.parse::<u32>().map(|value| value as i32))
+                .ok()
+                .map(Instruction::I32Const),
+            ValueType::I64 => value
+                .parse::<i64>()
+                .or_else(|_| value.parse::<u64>().map(|value| value as i64))
+                .ok()
+                .map(Instruction::I64Const),
+            ValueType::F32 => value
+                .parse::<f32>()
+                .ok()
+                .map(|value| Instruction::F32Const(value.to_bits())),
+            ValueType::F64 => value
+                .parse::<f64>()
+                .ok()
+                .map(|value| Instruction::F64Const(value.to_bits())),
+        }
+        .ok_or(format!("Invalid {} value '{}'", content_type, value))?;
+
+        self.set_global_init(
+            global_index,
+            InitExpr::new(vec![constant, Instruction::End]),
+        )
+    }
+
+    /// # Takes a module, a global index and a mutability and sets the mutability of the global.
+    ///
+    /// # Errors
+    /// - Returns an error if the global is imported or not found.
+    /// - Returns an error if the global is made immutable while the code sets it.
+    fn set_global_mutability(
+        &mut self,
+        global_index: usize,
+        is_mutable: bool,
+    ) -> Result<(), String> {
+        let is_set = self.code_section().is_some_and(|code_section| {
+            code_section
+                .bodies()
+                .iter()
+                .flat_map(|body| body.code().elements())
+                .any(|instruction| *instruction == Instruction::SetGlobal(global_index as u32))
+        });
+        if !is_mutable && is_set {
+            return Err(format!(
+                "Global {} is set by the code, so it can't be immutable",
+                global_index
+            ));
+        }
+
+        let global_entry = self.get_global_entry(global_index)?;
+        let content_type = global_entry.global_type().content_type();
+        *global_entry.global_type_mut() = GlobalType::new(content_type, is_mutable);
+
+        Ok(())
+    }
+}
+
+/// # Takes a symbol in the legacy Rust mangling scheme and returns its path without the hash,
+/// e.g. `_ZN4core9panicking5panic17hea78c4e8b318407dE` becomes `core::panicking::panic`.
+/// Returns `None` if the symbol is not mangled.
+fn demangle(symbol: &str) -> Option<String> {
+    let mut rest = symbol.strip_prefix("_ZN")?;
+    let mut path = vec![];
+
+    while !rest.starts_with('E') {
+        let digits = rest.find(|c: char| !c.is_ascii_digit())?;
+        let length: usize = rest[..digits].parse().ok()?;
+        // The length comes from the module, so it can't be trusted not to overflow
+        let end = digits.checked_add(length)?;
+        let segment = rest.get(digits..end)?;
+        path.push(segment);
+        rest = &rest[end..];
+    }
+
+    // The last segment is the hash, e.g. `h6f802c9b9dad6fdb`
+    if path
+        .last()
+        .is_some_and(|segment| segment.len() == 17 && segment.starts_with('h'))
+    {
+        path.pop();
+    }
+
+    Some(path.join("::"))
+}
+
+/// # Returns a memory type with the given limits, which is shared if the given memory type is.
+/// The shared flag is only accessible through the `atomics` feature of parity-wasm, which wasm-instrument
+/// doesn't build with, so the flags of the encoded limits are carried over instead.
+fn memory_type_with_limits(
+    memory_type: &MemoryType,
+    initial_pages: u32,
+    maximum_pages: Option<u32>,
+) -> Result<MemoryType, String> {
+    const FLAG_HAS_MAX: u8 = 0x01;
+    const FLAG_SHARED: u8 = 0x02;
+
+    let mut encoded = vec![];
+    memory_type
+        .serialize(&mut encoded)
+        .map_err(|err| format!("Could not encode the memory type: {}", err))?;
+    let shared_flag = encoded.first().ok_or("Empty memory type")? & FLAG_SHARED;
+    if shared_flag != 0 && maximum_pages.is_none() {
+        return Err("A shared memory needs maximum pages".to_string());
+    }
+
+    let has_max_flag = if maximum_pages.is_some() {
+        FLAG_HAS_MAX
+    } else {
+        0
+    };
+    let mut encoded = vec![shared_flag | has_max_flag];
+    VarUint32::from(initial_pages)
+        .serialize(&mut encoded)
+        .map_err(|err| format!("Could not encode the initial pages: {}", err))?;
+    if let Some(maximum_pages) = maximum_pages {
+        VarUint32::from(maximum_pages)
+            .serialize(&mut encoded)
+            .map_err(|err| format!("Could not encode the maximum pages: {}", err))?;
+    }
+
+    MemoryType::deserialize(&mut encoded.as_slice())
+        .map_err(|err| format!("Could not decode the memory type: {}", err))
+}
+
+/// # Takes a module and increments every reference to a function whose index is at least the given one.
+/// Calls, exports, table elements, the start function and the function and local names are updated.
+fn shift_function_indices(module: &mut Module, first_shifted_index: u32) -> Result<(), String> {
+    let shift = |index: &mut u32| {
+        if *index >= first_shifted_index {
+            *index += 1;
+        }
+    };
+
+    if let Some(code_section) = module.code_section_mut() {
+        code_section
+            .bodies_mut()
+            .iter_mut()
+            .flat_map(|body| body.code_mut().elements_mut().iter_mut())
+            .for_each(|instruction| {
+                if let Instruction::Call(index) = instruction {
+                    shift(index)
+                }
+            });
+    }
+
+    if let Some(export_section) = module.export_section_mut() {
+        export_section.entries_mut().iter_mut().for_each(|export| {
+            if let Function(index) = export.internal_mut() {
+                shift(index)
+            }
+        });
+    }
+
+    if let Some(elements_section) = module.elements_section_mut() {
+        elements_section
+            .entries_mut()
+            .iter_mut()
+            .flat_map(|segment| segment.members_mut().iter_mut())
+            .for_each(shift);
+    }
+
+    if let Some(mut start_function_index) = module.start_section() {
+        shift(&mut start_function_index);
+        module.set_start_section(start_function_index);
+    }
+
+    let Some(names) = module
+        .custom_sections()
+        .find(|section| section.name() == "name")
+    else {
+        return Ok(());
+    };
+
+    // Rebuild the `name` custom section, shifting the keys of the subsections indexed by functions
+    let mut reader = names.payload();
+    let mut payload = vec![];
+    while !reader.is_empty() {
+        let id: u8 = VarUint7::deserialize(&mut reader)
+            .map_err(|err| format!("Could not read name subsection id: {}", err))?
+            .into();
+        let size: usize = u32::from(
+            VarUint32::deserialize(&mut reader)
+                .map_err(|err| format!("Could not read name subsection size: {}", err))?,
+        ) as usize;
+
+        let (mut subsection, rest) = reader
+            .split_at_checked(size)
+            .ok_or("Name subsection is out of bounds")?;
+        let mut shifted_subsection = vec![];
+        let serialized = match id {
+            FUNCTION_NAMES_SUBSECTION_ID => {
+                IndexMap::<String>::deserialize(usize::MAX, &mut subsection)
+                    .map_err(|err| format!("Could not read name map: {}", err))?
+                    .into_iter()
+                    .map(|(mut index, name)| {
+                        shift(&mut index);
+                        (index, name)
+                    })
+                    .collect::<IndexMap<_>>()
+                    .serialize(&mut shifted_subsection)
+            }
+            LOCAL_NAMES_SUBSECTION_ID => IndexMap::deserialize_with(
+                usize::MAX,
+                &|_, reader| IndexMap::<String>::deserialize(usize::MAX, reader),
+                &mut subsection,
+            )
+            .map_err(|err| format!("Could not read indirect name map: {}", err))?
+            .into_iter()
+            .map(|(mut index, local_names)| {
+                shift(&mut index);
+                (index, local_names)
+            })
+            .collect::<IndexMap<_>>()
+            .serialize(&mut shifted_subsection),
+            _ => {
+                shifted_subsection.extend_from_slice(subsection);
+                Ok(())
+            }
+        };
+        serialized.map_err(|err| format!("Could not write name subsection: {}", err))?;
+
+        payload.push(id);
+        VarUint32::from(shifted_subsection.len())
+            .serialize(&mut payload)
+            .map_err(|err| format!("Could not write name subsection size: {}", err))?;
+        payload.append(&mut shifted_subsection);
+
+        reader = rest;
+    }
+    module.set_custom_section("name", payload);
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod injector_tests {
     use super::*;
-    use crate::util::load_module_from_wasm;
+    use crate::util::{load_module_from_wasm, module_from_blob};
     use std::path::Path;
+    use wasm_instrument::parity_wasm::elements::{Instructions, ValueType};
 
     /// WARNING: VALUES ARE FOR TEST WASM ONLY AND WILL DIFFER FOR DIFFERENT WASM BLOBS!!!
     const VALIDATE_BLOCK_GLOBAL_INDEX: usize = 1733;
     const IMPORT_SECTION_LENGTH: usize = 39;
     const MALLOC_INDEX: usize = 25;
+    const FREE_INDEX: usize = 24;
     const WASM_INSTRUCTION_COUNT: usize = 358;
-    const WASM_PATH: &'static str = concat!(env!("CARGO_MANIFEST_DIR"), "/samples/example.wasm");
+    const FUNCTION_COUNT: usize = 3606;
+    const TYPE_COUNT: usize = 78;
+    const VALIDATE_BLOCK_NEXT_LOCAL_INDEX: usize = 7;
+    const STACK_POINTER_INDEX: usize = 0;
+    const HEAP_BASE_INDEX: usize = 2;
+    const RUST_BEGIN_UNWIND_INDEX: usize = 2686;
+    const CORE_PANICKING_PANIC_INDEX: usize = 91;
+    const MEMORY_INITIAL_PAGES: u32 = 19;
+    const STACK_POINTER: i32 = 1048576;
+    const HEAP_BASE: i32 = 1188576;
+    const WASM_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/samples/example.wasm");
 
     fn load_module() -> Module {
         let module_path = Path::new(WASM_PATH);
-        let module = load_module_from_wasm(module_path).unwrap();
-        module
+        load_module_from_wasm(module_path).unwrap()
+    }
+
+    fn module_from_wat(wat: &str) -> Module {
+        module_from_blob(&wat::parse_str(wat).unwrap()).unwrap()
     }
 
     #[test]
@@ -195,4 +1125,421 @@ mod injector_tests {
         let malloc_index = module.get_malloc_index().unwrap();
         assert_eq!(malloc_index, MALLOC_INDEX);
     }
+
+    #[test]
+    fn test_add_function() {
+        let mut module = load_module();
+        let function_index = module
+            .add_function(
+                FunctionType::default(),
+                FuncBody::new(vec![], Instructions::empty()),
+            )
+            .unwrap();
+        assert_eq!(function_index, FUNCTION_COUNT);
+    }
+
+    #[test]
+    fn test_get_function_type() {
+        let mut module = load_module();
+        let function_type = module
+            .get_function_type(VALIDATE_BLOCK_GLOBAL_INDEX)
+            .unwrap();
+        assert_eq!(
+            function_type,
+            FunctionType::new(vec![ValueType::I32, ValueType::I32], vec![ValueType::I64])
+        );
+
+        let malloc_type = module.get_function_type(MALLOC_INDEX).unwrap();
+        assert_eq!(
+            malloc_type,
+            FunctionType::new(vec![ValueType::I32], vec![ValueType::I32])
+        );
+    }
+
+    #[test]
+    fn test_get_or_add_type_index() {
+        let mut module = load_module();
+        let existing_type = FunctionType::new(vec![ValueType::I32], vec![ValueType::I32]);
+        assert!(module.get_or_add_type_index(existing_type).unwrap() < TYPE_COUNT);
+
+        let new_type = FunctionType::new(vec![ValueType::F64; 3], vec![ValueType::F32]);
+        assert_eq!(
+            module.get_or_add_type_index(new_type.clone()).unwrap(),
+            TYPE_COUNT
+        );
+        assert_eq!(module.get_or_add_type_index(new_type).unwrap(), TYPE_COUNT);
+    }
+
+    #[test]
+    fn test_get_next_local_index() {
+        let mut module = load_module();
+        let next_local_index = module.get_next_local_index("validate_block").unwrap();
+        assert_eq!(next_local_index, VALIDATE_BLOCK_NEXT_LOCAL_INDEX);
+    }
+
+    #[test]
+    fn test_get_name_map() {
+        let mut module = load_module();
+        let function_names = module.get_name_map(FUNCTION_NAMES_SUBSECTION_ID).unwrap();
+        assert_eq!(
+            function_names.get(MALLOC_INDEX as u32).unwrap(),
+            "ext_allocator_malloc_version_1"
+        );
+
+        let global_names = module.get_name_map(GLOBAL_NAMES_SUBSECTION_ID).unwrap();
+        assert_eq!(
+            global_names.get(STACK_POINTER_INDEX as u32).unwrap(),
+            "__stack_pointer"
+        );
+    }
+
+    #[test]
+    fn test_get_global_index() {
+        let mut module = load_module();
+        assert_eq!(
+            module.get_global_index("__stack_pointer").unwrap(),
+            STACK_POINTER_INDEX
+        );
+        assert_eq!(
+            module.get_global_index("__heap_base").unwrap(),
+            HEAP_BASE_INDEX
+        );
+        assert!(module.get_global_index("__no_such_global").is_err());
+    }
+
+    #[test]
+    fn test_get_named_function_index() {
+        let mut module = load_module();
+        assert_eq!(
+            module
+                .get_named_function_index("rust_begin_unwind")
+                .unwrap(),
+            RUST_BEGIN_UNWIND_INDEX
+        );
+        assert_eq!(
+            module
+                .get_named_function_index("core::panicking::panic")
+                .unwrap(),
+            CORE_PANICKING_PANIC_INDEX
+        );
+        assert!(module.get_named_function_index("core::panicking").is_err());
+    }
+
+    #[test]
+    fn test_set_memory_limits() {
+        let mut module = load_module();
+        let limits = module.get_memory_limits().unwrap();
+        assert_eq!(limits.initial(), MEMORY_INITIAL_PAGES);
+        assert_eq!(limits.maximum(), None);
+
+        // Initial pages above 4 GiB and maximum below initial
+        module.set_memory_limits(u32::MAX, Some(1)).unwrap();
+        let limits = module.get_memory_limits().unwrap();
+        assert_eq!(limits.initial(), u32::MAX);
+        assert_eq!(limits.maximum(), Some(1));
+    }
+
+    #[test]
+    fn test_set_memory_limits_defined_memory() {
+        let mut module = module_from_wat(r#"(module (memory (export "memory") 17 32))"#);
+        module.set_memory_limits(17, None).unwrap();
+
+        let limits = module.get_memory_limits().unwrap();
+        assert_eq!(limits.initial(), 17);
+        assert_eq!(limits.maximum(), None);
+    }
+
+    #[test]
+    fn test_convert_memory() {
+        let mut module = load_module();
+        let data_segments_count = module.data_section().unwrap().entries().len();
+
+        module.convert_memory(MemoryDeclaration::Exported).unwrap();
+        assert!(module
+            .import_section()
+            .unwrap()
+            .entries()
+            .iter()
+            .all(|entry| !matches!(entry.external(), External::Memory(_))));
+        assert!(module
+            .export_section()
+            .unwrap()
+            .entries()
+            .iter()
+            .any(|export| export.field() == "memory" && *export.internal() == Internal::Memory(0)));
+        assert_eq!(
+            module.get_memory_limits().unwrap().initial(),
+            MEMORY_INITIAL_PAGES
+        );
+        assert_eq!(
+            module.get_import_section_len().unwrap(),
+            IMPORT_SECTION_LENGTH
+        );
+
+        module.convert_memory(MemoryDeclaration::Imported).unwrap();
+        assert!(module.memory_section().is_none());
+        assert_eq!(
+            module.get_memory_limits().unwrap().initial(),
+            MEMORY_INITIAL_PAGES
+        );
+        assert_eq!(
+            module.data_section().unwrap().entries().len(),
+            data_segments_count
+        );
+    }
+
+    #[test]
+    fn test_convert_memory_with_memory_export() {
+        let mut module = module_from_wat(
+            r#"(module
+                (import "env" "memory" (memory 1))
+                (func $f)
+                (export "memory" (func $f)))"#,
+        );
+        let original = module.clone();
+
+        assert!(module.convert_memory(MemoryDeclaration::Exported).is_err());
+        assert_eq!(module, original);
+    }
+
+    #[test]
+    fn test_convert_memory_without_memory() {
+        let mut module = module_from_wat("(module)");
+        assert!(module.convert_memory(MemoryDeclaration::Exported).is_err());
+    }
+
+    #[test]
+    fn test_get_global_i32_value() {
+        let mut module = load_module();
+        assert_eq!(
+            module.get_global_i32_value("__heap_base").unwrap(),
+            HEAP_BASE
+        );
+        assert_eq!(
+            module.get_global_i32_value("__stack_pointer").unwrap(),
+            STACK_POINTER
+        );
+    }
+
+    #[test]
+    fn test_tamper_heap_base() {
+        let tampered_heap_bases = [
+            (HeapBaseTampering::BelowDataEnd, 1188568),
+            (HeapBaseTampering::InsideStack, STACK_POINTER / 2),
+            (HeapBaseTampering::Unaligned, HEAP_BASE + 1),
+            (
+                HeapBaseTampering::PastMemory,
+                MEMORY_INITIAL_PAGES as i32 * 65536,
+            ),
+        ];
+
+        for (tampering, tampered_heap_base) in tampered_heap_bases {
+            let mut module = load_module();
+            module.tamper_heap_base(tampering).unwrap();
+            assert_eq!(
+                module.get_global_i32_value("__heap_base").unwrap(),
+                tampered_heap_base
+            );
+        }
+    }
+
+    #[test]
+    fn test_tamper_heap_base_past_4_gib_memory() {
+        let mut module = load_module();
+        module.set_memory_limits(65535, None).unwrap();
+        module
+            .tamper_heap_base(HeapBaseTampering::PastMemory)
+            .unwrap();
+        assert_eq!(
+            module.get_global_i32_value("__heap_base").unwrap() as u32,
+            65535 * 65536
+        );
+
+        module.set_memory_limits(65536, None).unwrap();
+        assert!(module
+            .tamper_heap_base(HeapBaseTampering::PastMemory)
+            .is_err());
+    }
+
+    #[test]
+    fn test_tamper_heap_base_remove_export() {
+        let mut module = load_module();
+        module
+            .tamper_heap_base(HeapBaseTampering::RemoveExport)
+            .unwrap();
+        assert!(module
+            .export_section()
+            .unwrap()
+            .entries()
+            .iter()
+            .all(|export| export.field() != "__heap_base"));
+        assert!(module
+            .tamper_heap_base(HeapBaseTampering::RemoveExport)
+            .is_err());
+    }
+
+    #[test]
+    fn test_resolve_global_index() {
+        let mut module = load_module();
+        assert_eq!(
+            module.resolve_global_index("__stack_pointer").unwrap(),
+            STACK_POINTER_INDEX
+        );
+        assert_eq!(module.resolve_global_index("2").unwrap(), HEAP_BASE_INDEX);
+        assert!(module.resolve_global_index("3").is_err());
+    }
+
+    #[test]
+    fn test_set_global_value() {
+        let mut module = load_module();
+        module
+            .set_global_value(STACK_POINTER_INDEX, "4294967295")
+            .unwrap();
+        assert_eq!(module.get_global_i32_value("__stack_pointer").unwrap(), -1);
+
+        assert!(module.set_global_value(STACK_POINTER_INDEX, "1.5").is_err());
+        assert!(module
+            .set_global_init(
+                STACK_POINTER_INDEX,
+                InitExpr::new(vec![Instruction::I64Const(0), Instruction::End])
+            )
+            .is_err());
+    }
+
+    #[test]
+    fn test_set_global_value_float() {
+        let mut module = module_from_wat(
+            r#"
+            (module
+                (import "env" "base" (global $base f64))
+                (global (mut f64) (f64.const 0))
+            )
+            "#,
+        );
+        module.set_global_value(1, "1.5").unwrap();
+        assert_eq!(
+            module.get_global_entry(1).unwrap().init_expr().code(),
+            [Instruction::F64Const(1.5_f64.to_bits()), Instruction::End]
+        );
+
+        // Imported immutable globals are constant expressions
+        module
+            .set_global_init(
+                1,
+                InitExpr::new(vec![Instruction::GetGlobal(0), Instruction::End]),
+            )
+            .unwrap();
+        assert!(module.set_global_value(0, "1.5").is_err());
+    }
+
+    #[test]
+    fn test_set_global_mutability() {
+        let mut module = load_module();
+        module.set_global_mutability(HEAP_BASE_INDEX, true).unwrap();
+        assert!(module
+            .get_global_type(HEAP_BASE_INDEX)
+            .unwrap()
+            .is_mutable());
+
+        // The stack pointer is set by the code
+        assert!(module
+            .set_global_mutability(STACK_POINTER_INDEX, false)
+            .is_err());
+    }
+
+    #[test]
+    fn test_memory_type_with_limits() {
+        let memory_type = MemoryType::new(1, Some(2));
+
+        let with_limits = memory_type_with_limits(&memory_type, 3, None).unwrap();
+        assert_eq!(with_limits.limits().initial(), 3);
+        assert_eq!(with_limits.limits().maximum(), None);
+
+        let with_limits = memory_type_with_limits(&with_limits, 0, Some(u32::MAX)).unwrap();
+        assert_eq!(with_limits.limits().initial(), 0);
+        assert_eq!(with_limits.limits().maximum(), Some(u32::MAX));
+    }
+
+    #[test]
+    fn test_demangle() {
+        assert_eq!(
+            demangle("_ZN4core9panicking5panic17hea78c4e8b318407dE").unwrap(),
+            "core::panicking::panic"
+        );
+        assert!(demangle("rust_begin_unwind").is_none());
+        assert!(demangle("_ZN18446744073709551615aE").is_none());
+    }
+
+    #[test]
+    fn test_get_free_index() {
+        let mut module = load_module();
+        let free_index = module.get_free_index().unwrap();
+        assert_eq!(free_index, FREE_INDEX);
+    }
+
+    #[test]
+    fn test_get_imported_function_index() {
+        let mut module = load_module();
+        assert_eq!(
+            module
+                .get_imported_function_index("ext_allocator_free_version_1")
+                .unwrap(),
+            FREE_INDEX
+        );
+        assert_eq!(
+            module
+                .get_imported_function_index("ext_allocator_free")
+                .unwrap(),
+            FREE_INDEX
+        );
+        assert!(module.get_imported_function_index("ext_allocator").is_err());
+    }
+
+    #[test]
+    fn test_get_or_add_imported_function() {
+        let mut module = load_module();
+        let malloc_type = FunctionType::new(vec![ValueType::I32], vec![ValueType::I32]);
+        assert_eq!(
+            module
+                .get_or_add_imported_function("ext_allocator_malloc_version_1", malloc_type)
+                .unwrap(),
+            MALLOC_INDEX
+        );
+        assert_eq!(
+            module.get_import_section_len().unwrap(),
+            IMPORT_SECTION_LENGTH
+        );
+
+        let timestamp_type = FunctionType::new(vec![], vec![ValueType::I64]);
+        assert_eq!(
+            module
+                .get_or_add_imported_function("ext_offchain_timestamp_version_1", timestamp_type)
+                .unwrap(),
+            IMPORT_SECTION_LENGTH
+        );
+
+        // The following functions are shifted by the new import
+        assert_eq!(
+            module.get_global_function_index("validate_block").unwrap(),
+            VALIDATE_BLOCK_GLOBAL_INDEX + 1
+        );
+        let function_names = module.get_name_map(FUNCTION_NAMES_SUBSECTION_ID).unwrap();
+        assert_eq!(
+            function_names
+                .get(VALIDATE_BLOCK_GLOBAL_INDEX as u32 + 1)
+                .unwrap(),
+            "validate_block"
+        );
+        assert_eq!(
+            function_names.get(MALLOC_INDEX as u32).unwrap(),
+            "ext_allocator_malloc_version_1"
+        );
+        assert!(module
+            .elements_section()
+            .unwrap()
+            .entries()
+            .iter()
+            .flat_map(|segment| segment.members())
+            .all(|index| *index as usize > IMPORT_SECTION_LENGTH));
+    }
 }
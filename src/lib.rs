@@ -5,7 +5,7 @@
 //! # Example
 //! ```
 //! use std::path::Path;
-//! use wasm_injector::{Injection, load_module_from_wasm, save_module_to_wasm};
+//! use wasm_injector::{Injection, InjectionOptions, load_module_from_wasm, save_module_to_wasm};
 //!
 //! # fn main() -> Result<(), String> {
 //! let source = Path::new("samples/example.wasm");
@@ -15,7 +15,7 @@
 //!
 //! let mut module = load_module_from_wasm(source)?; // supply your own path here
 //! let injection = Injection::StackOverflow; // choose your injection
-//! injection.inject(&mut module, "validate_block", None)?; // inject the instruction into the specified wasm export function
+//! injection.inject(&mut module, "validate_block", &InjectionOptions::default())?; // inject the instruction into the specified wasm export function
 //!
 //! save_module_to_wasm(module, destination, compressed, hexified)?; // save the module in your destination. You can choose to compress and/or hexify the module.
 //!     
@@ -28,6 +28,7 @@ pub mod injecting;
 pub mod util;
 
 pub use self::injecting::injections::Injection;
+pub use self::injecting::injections::InjectionOptions;
 pub use self::util::blob_from_module;
 pub use self::util::hexify_bytes;
 pub use self::util::load_module_from_wasm;
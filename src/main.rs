@@ -2,8 +2,10 @@ use clap::{
     builder::ArgPredicate, error::ErrorKind, CommandFactory, Parser, Subcommand, ValueHint,
 };
 use std::path::PathBuf;
-use wasm_injector::injecting::injections::Injection;
+use wasm_injector::injecting::injections::{Injection, InjectionOptions, ValidationResultFault};
+use wasm_injector::injecting::injector::{HeapBaseTampering, MemoryDeclaration, ModuleMapper};
 use wasm_injector::util::{load_module_from_wasm, modify_file_name, save_module_to_wasm};
+use wasm_instrument::parity_wasm::elements::Module;
 
 #[derive(Parser, Debug, PartialEq, Eq)]
 #[command(author, version, about, long_about = None)]
@@ -12,23 +14,53 @@ struct Cli {
     action: Action,
 }
 
+// The action is parsed once, so the size of the injection options doesn't matter
+#[allow(clippy::large_enum_variant)]
 #[derive(Debug, Subcommand, PartialEq, Eq)]
 enum Action {
     #[command(about = "Inject invalid instructions into a wasm module")]
     Inject {
-        #[arg(value_enum, required = true, requires_if("noops", "size"), value_name = "injection",value_hint = ValueHint::Other)]
+        #[arg(
+            value_enum,
+            required = true,
+            requires_if("noops", "size"),
+            requires_if("arithmetic-trap", "trap"),
+            requires_if("indirect-call-fault", "indirect_call_fault"),
+            requires_if("bounded-loop", "iterations"),
+            requires_if("bad-pointer-length", "pointer_length_fault"),
+            requires_if("bad-validation-result", "validation_result_fault"),
+            requires_if("host-function-misuse", "host_function"),
+            requires_if("host-function-misuse", "host_function_misuse"),
+            requires_if("allocator-misuse", "allocator_misuse"),
+            requires_if("allocator-header-corruption", "header_corruption"),
+            requires_if("allocator-fragmentation", "allocation_sizes"),
+            requires_if("allocator-fragmentation", "iterations"),
+            requires_if("storage-write-spam", "iterations"),
+            requires_if("storage-write-spam", "value_size"),
+            requires_if("logging-spam", "message_size"),
+            requires_if("logging-spam", "log_level"),
+            requires_if("host-cpu-burn", "host_cpu_burn"),
+            requires_if("host-cpu-burn", "iterations"),
+            requires_if("host-cpu-burn", "buffer_size"),
+            requires_if("forbidden-host-call", "forbidden_host_function"),
+            requires_if("runtime-panic", "panic_message"),
+            requires_if("stack-pointer-corruption", "stack_pointer_corruption"),
+            requires_if("out-of-bounds-access", "address"),
+            requires_if("out-of-bounds-access", "width"),
+            requires_if("out-of-bounds-access", "access"),
+            value_name = "injection",
+            value_hint = ValueHint::Other
+        )]
         injection: Injection,
 
         #[arg(required = true, value_name = "function", help = "The name of the exported function to be injected with the instructions", value_hint = ValueHint::Other)]
         function: String,
 
-        #[arg(
-            long,
-            value_name = "size", 
-            help = "The number of noops to be injected in MB (1 NOP = 1 byte)", 
-            value_hint = ValueHint::Other
-        )]
-        size: Option<i16>,
+        #[command(flatten)]
+        options: InjectionOptions,
+
+        #[command(flatten)]
+        mutation_opts: MutationOpts,
 
         #[command(flatten)]
         global_opts: GlobalOpts,
@@ -53,6 +85,9 @@ enum Action {
         about = "Convert from `hexified` and/or `compressed` to `raw` wasm module and vice versa"
     )]
     Convert {
+        #[command(flatten)]
+        mutation_opts: MutationOpts,
+
         #[command(flatten)]
         global_opts: GlobalOpts,
 
@@ -77,6 +112,48 @@ enum Action {
         )]
         compressed: bool,
 
+        #[arg(
+            long,
+            value_name = "hexified",
+            help = "Hexifies the wasm. Can be used with `--compressed`",
+            default_value_t = false
+        )]
+        hexified: bool,
+    },
+    #[command(about = "Edit the init expression and the mutability of a global in a wasm module")]
+    Global {
+        #[arg(required = true, value_name = "global", help = "The index, export name or `name` section name of the global", value_hint = ValueHint::Other)]
+        global: String,
+
+        #[arg(
+            long,
+            value_name = "value",
+            help = "Initializes the global with the value, parsed as the type of the global",
+            required_unless_present = "mutable",
+            allow_hyphen_values = true,
+            value_hint = ValueHint::Other
+        )]
+        value: Option<String>,
+
+        #[arg(
+            long,
+            value_name = "mutable",
+            help = "Sets the mutability of the global",
+            value_hint = ValueHint::Other
+        )]
+        mutable: Option<bool>,
+
+        #[command(flatten)]
+        global_opts: GlobalOpts,
+
+        #[arg(
+            long,
+            value_name = "compressed",
+            help = "Compresses the wasm. Can be used with `--hexified`",
+            default_value_t = false
+        )]
+        compressed: bool,
+
         #[arg(
             long,
             value_name = "hexified",
@@ -96,26 +173,259 @@ struct GlobalOpts {
     destination: Option<PathBuf>,
 }
 
+#[derive(clap::Args, Debug, Default, Clone, PartialEq, Eq)]
+struct MutationOpts {
+    #[arg(
+        long,
+        value_enum,
+        value_name = "memory-declaration",
+        help = "Converts the memory to be imported from `env` or defined and exported, preserving its limits and data",
+        value_hint = ValueHint::Other
+    )]
+    memory_declaration: Option<MemoryDeclaration>,
+
+    #[arg(
+        long,
+        value_name = "initial-pages",
+        help = "Rewrites the initial pages of the memory, which can exceed the 4 GiB limit",
+        value_hint = ValueHint::Other
+    )]
+    initial_pages: Option<u32>,
+
+    #[arg(
+        long,
+        value_name = "maximum-pages",
+        help = "Rewrites the maximum pages of the memory, which can be below the initial pages",
+        conflicts_with = "remove_maximum_pages",
+        value_hint = ValueHint::Other
+    )]
+    maximum_pages: Option<u32>,
+
+    #[arg(
+        long,
+        value_name = "remove-maximum-pages",
+        help = "Removes the maximum pages of the memory",
+        default_value_t = false
+    )]
+    remove_maximum_pages: bool,
+
+    #[arg(
+        long,
+        value_enum,
+        value_name = "heap-base-tampering",
+        help = "Tampers with the exported `__heap_base` global, where the host allocator starts",
+        value_hint = ValueHint::Other
+    )]
+    heap_base_tampering: Option<HeapBaseTampering>,
+}
+
+impl MutationOpts {
+    /// # Takes a module and applies the selected mutations to the module.
+    fn mutate(&self, module: &mut Module) -> Result<(), String> {
+        if let Some(memory_declaration) = self.memory_declaration {
+            module.convert_memory(memory_declaration)?;
+        }
+
+        if self.initial_pages.is_some() || self.maximum_pages.is_some() || self.remove_maximum_pages
+        {
+            let limits = module.get_memory_limits()?;
+            let initial_pages = self.initial_pages.unwrap_or(limits.initial());
+            let maximum_pages = match self.remove_maximum_pages {
+                true => None,
+                false => self.maximum_pages.or(limits.maximum()),
+            };
+
+            module.set_memory_limits(initial_pages, maximum_pages)?;
+        }
+
+        if let Some(heap_base_tampering) = self.heap_base_tampering {
+            module.tamper_heap_base(heap_base_tampering)?;
+        }
+
+        Ok(())
+    }
+}
+
 fn main() -> Result<(), String> {
     let Cli { action } = Cli::parse();
 
     if let Action::Inject {
-        injection, size, ..
+        injection, options, ..
     } = &action
     {
-        match injection {
-            Injection::Noops => {}
-            _ => {
-                if size.is_some() {
-                    let mut cmd = Cli::command();
-                    cmd.error(
-                        ErrorKind::ArgumentConflict,
-                        "The `size` argument is only valid for the `noops` injection".to_string(),
-                    )
-                    .exit();
-                }
+        // Every option is only valid for the injections that use it
+        let option_usages: &[(&str, bool, &[Injection])] = &[
+            ("size", options.size.is_some(), &[Injection::Noops]),
+            ("trap", options.trap.is_some(), &[Injection::ArithmeticTrap]),
+            (
+                "locals",
+                options.locals.is_some(),
+                &[Injection::StackExhaustion],
+            ),
+            (
+                "indirect-call-fault",
+                options.indirect_call_fault.is_some(),
+                &[Injection::IndirectCallFault],
+            ),
+            (
+                "iterations",
+                options.iterations.is_some(),
+                &[
+                    Injection::BoundedLoop,
+                    Injection::AllocatorFragmentation,
+                    Injection::StorageWriteSpam,
+                    Injection::LoggingSpam,
+                    Injection::HostCpuBurn,
+                ],
+            ),
+            (
+                "pointer-length-fault",
+                options.pointer_length_fault.is_some(),
+                &[Injection::BadPointerLength],
+            ),
+            (
+                "validation-result-fault",
+                options.validation_result_fault.is_some(),
+                &[Injection::BadValidationResult],
+            ),
+            (
+                "upward-messages",
+                options.upward_messages.is_some(),
+                &[Injection::BadValidationResult],
+            ),
+            (
+                "host-function",
+                options.host_function.is_some(),
+                &[Injection::HostFunctionMisuse],
+            ),
+            (
+                "host-function-misuse",
+                options.host_function_misuse.is_some(),
+                &[Injection::HostFunctionMisuse],
+            ),
+            (
+                "allocator-misuse",
+                options.allocator_misuse.is_some(),
+                &[Injection::AllocatorMisuse],
+            ),
+            (
+                "header-corruption",
+                options.header_corruption.is_some(),
+                &[Injection::AllocatorHeaderCorruption],
+            ),
+            (
+                "allocation-sizes",
+                !options.allocation_sizes.is_empty(),
+                &[Injection::AllocatorFragmentation],
+            ),
+            (
+                "free-every",
+                options.free_every.is_some(),
+                &[Injection::AllocatorFragmentation],
+            ),
+            (
+                "value-size",
+                options.value_size.is_some(),
+                &[Injection::StorageWriteSpam],
+            ),
+            (
+                "storage-key",
+                options.storage_key.is_some(),
+                &[Injection::StorageProofMiss],
+            ),
+            (
+                "message-size",
+                options.message_size.is_some(),
+                &[Injection::LoggingSpam],
+            ),
+            (
+                "log-level",
+                options.log_level.is_some(),
+                &[Injection::LoggingSpam],
+            ),
+            (
+                "host-cpu-burn",
+                options.host_cpu_burn.is_some(),
+                &[Injection::HostCpuBurn],
+            ),
+            (
+                "buffer-size",
+                options.buffer_size.is_some(),
+                &[Injection::HostCpuBurn],
+            ),
+            (
+                "forbidden-host-function",
+                options.forbidden_host_function.is_some(),
+                &[Injection::ForbiddenHostCall],
+            ),
+            (
+                "panic-message",
+                options.panic_message.is_some(),
+                &[Injection::RuntimePanic],
+            ),
+            (
+                "stack-pointer-corruption",
+                options.stack_pointer_corruption.is_some(),
+                &[Injection::StackPointerCorruption],
+            ),
+            (
+                "address",
+                options.address.is_some(),
+                &[Injection::OutOfBoundsAccess],
+            ),
+            (
+                "offset",
+                options.offset.is_some(),
+                &[Injection::OutOfBoundsAccess],
+            ),
+            (
+                "width",
+                options.width.is_some(),
+                &[Injection::OutOfBoundsAccess],
+            ),
+            (
+                "access",
+                options.access.is_some(),
+                &[Injection::OutOfBoundsAccess],
+            ),
+            (
+                "alignment",
+                options.alignment.is_some(),
+                &[Injection::OutOfBoundsAccess],
+            ),
+        ];
+
+        for (option, is_present, injections) in option_usages {
+            if *is_present && !injections.contains(injection) {
+                let injections = injections
+                    .iter()
+                    .map(|injection| format!("`{}`", injection))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+
+                let mut cmd = Cli::command();
+                cmd.error(
+                    ErrorKind::ArgumentConflict,
+                    format!(
+                        "The `{}` argument is only valid for the {} injection",
+                        option, injections
+                    ),
+                )
+                .exit();
             }
         }
+
+        // The number of upward messages is only used by one of the validation result faults
+        if options.upward_messages.is_some()
+            && options.validation_result_fault != Some(ValidationResultFault::TooManyUpwardMessages)
+        {
+            let mut cmd = Cli::command();
+            cmd.error(
+                ErrorKind::ArgumentConflict,
+                "The `upward-messages` argument is only valid for the `too-many-upward-messages` validation result fault",
+            )
+            .exit();
+        }
     }
 
     let calculate_default_destination_file_name = |file_name: &str| {
@@ -152,24 +462,55 @@ fn main() -> Result<(), String> {
                     file_name = format!("hexified-{}.hex", file_name);
                 }
             }
+            Action::Global {
+                compressed,
+                hexified,
+                ..
+            } => {
+                file_name = format!("global-{}.wasm", file_name);
+                if *compressed {
+                    file_name = format!("compressed-{}", file_name);
+                }
+                if *hexified {
+                    file_name = format!("hexified-{}.hex", file_name);
+                }
+            }
         }
 
         file_name
     };
 
-    let (global_opts, hexified, compressed) = match &action {
+    let (global_opts, mutation_opts, hexified, compressed) = match &action {
         Action::Inject {
             global_opts,
+            mutation_opts,
             hexified,
             compressed,
             ..
         }
         | Action::Convert {
+            global_opts,
+            mutation_opts,
+            hexified,
+            compressed,
+            ..
+        } => (
+            global_opts.clone(),
+            mutation_opts.clone(),
+            *hexified,
+            *compressed,
+        ),
+        Action::Global {
             global_opts,
             hexified,
             compressed,
             ..
-        } => (global_opts.clone(), *hexified, *compressed),
+        } => (
+            global_opts.clone(),
+            MutationOpts::default(),
+            *hexified,
+            *compressed,
+        ),
     };
 
     let destination = match global_opts.destination {
@@ -187,15 +528,35 @@ fn main() -> Result<(), String> {
     // Get the module
     let mut module = load_module_from_wasm(global_opts.source.as_path())?;
 
-    if let Action::Inject {
-        injection,
-        function,
-        size,
-        ..
-    } = action
-    {
-        // Inject the module
-        injection.inject(&mut module, &function, size)?;
+    // Mutate the module
+    mutation_opts.mutate(&mut module)?;
+
+    match action {
+        Action::Inject {
+            injection,
+            function,
+            options,
+            ..
+        } => {
+            // Inject the module
+            injection.inject(&mut module, &function, &options)?;
+        }
+        Action::Global {
+            global,
+            value,
+            mutable,
+            ..
+        } => {
+            // Edit the global
+            let global_index = module.resolve_global_index(&global)?;
+            if let Some(value) = value {
+                module.set_global_value(global_index, &value)?;
+            }
+            if let Some(is_mutable) = mutable {
+                module.set_global_mutability(global_index, is_mutable)?;
+            }
+        }
+        Action::Convert { .. } => {}
     }
 
     save_module_to_wasm(module, destination.as_path(), compressed, hexified)?;
@@ -206,12 +567,17 @@ fn main() -> Result<(), String> {
 #[cfg(test)]
 mod cli_tests {
     use super::*;
+    use wasm_injector::injecting::injections::{
+        AccessWidth, AllocatorMisuse, ArithmeticTrap, ForbiddenHostFunction, HeaderCorruption,
+        HostCpuBurn, HostFunctionMisuse, IndirectCallFault, LogLevel, MemoryAccess,
+        PointerLengthFault, StackPointerCorruption, ValidationResultFault,
+    };
 
     const FUNCTION_NAME: &str = "validate_block";
 
     #[test]
     fn test_invalid_subcommand() {
-        let result = Cli::try_parse_from(&["test", "invalid"]);
+        let result = Cli::try_parse_from(["test", "invalid"]);
         assert!(result.is_err());
         assert_eq!(
             result.unwrap_err().kind(),
@@ -221,13 +587,13 @@ mod cli_tests {
 
     #[test]
     fn function_name_is_required() {
-        assert!(Cli::try_parse_from(&["test", "inject", "noops", "test.wasm"]).is_err())
+        assert!(Cli::try_parse_from(["test", "inject", "noops", "test.wasm"]).is_err())
     }
 
     #[test]
     fn test_inject_noops() {
         assert_eq!(
-            Cli::try_parse_from(&[
+            Cli::try_parse_from([
                 "test",
                 "inject",
                 "noops",
@@ -240,8 +606,12 @@ mod cli_tests {
             Cli {
                 action: Action::Inject {
                     injection: Injection::Noops,
-                    size: Some(20),
+                    options: InjectionOptions {
+                        size: Some(20),
+                        ..Default::default()
+                    },
                     function: FUNCTION_NAME.to_string(),
+                    mutation_opts: MutationOpts::default(),
                     global_opts: GlobalOpts {
                         source: PathBuf::from("test.wasm"),
                         destination: None
@@ -255,7 +625,7 @@ mod cli_tests {
 
     #[test]
     fn test_inject_noops_requires_size_arg() {
-        let result = Cli::try_parse_from(&["test", "inject", "noops", FUNCTION_NAME, "test.wasm"]);
+        let result = Cli::try_parse_from(["test", "inject", "noops", FUNCTION_NAME, "test.wasm"]);
         assert!(result.is_err());
         assert_eq!(
             result.unwrap_err().kind(),
@@ -266,7 +636,7 @@ mod cli_tests {
     #[test]
     fn test_inject_heap_overflow() {
         assert_eq!(
-            Cli::try_parse_from(&[
+            Cli::try_parse_from([
                 "test",
                 "inject",
                 "heap-overflow",
@@ -278,7 +648,8 @@ mod cli_tests {
                 action: Action::Inject {
                     injection: Injection::HeapOverflow,
                     function: FUNCTION_NAME.to_string(),
-                    size: None,
+                    options: InjectionOptions::default(),
+                    mutation_opts: MutationOpts::default(),
                     global_opts: GlobalOpts {
                         source: PathBuf::from("test.wasm"),
                         destination: None
@@ -293,7 +664,7 @@ mod cli_tests {
     #[test]
     fn test_inject_stack_overflow() {
         assert_eq!(
-            Cli::try_parse_from(&[
+            Cli::try_parse_from([
                 "test",
                 "inject",
                 "stack-overflow",
@@ -305,7 +676,8 @@ mod cli_tests {
                 action: Action::Inject {
                     injection: Injection::StackOverflow,
                     function: FUNCTION_NAME.to_string(),
-                    size: None,
+                    options: InjectionOptions::default(),
+                    mutation_opts: MutationOpts::default(),
                     global_opts: GlobalOpts {
                         source: PathBuf::from("test.wasm"),
                         destination: None
@@ -320,7 +692,7 @@ mod cli_tests {
     #[test]
     fn test_inject_bad_return_value() {
         assert_eq!(
-            Cli::try_parse_from(&[
+            Cli::try_parse_from([
                 "test",
                 "inject",
                 "bad-return-value",
@@ -332,7 +704,8 @@ mod cli_tests {
                 action: Action::Inject {
                     injection: Injection::BadReturnValue,
                     function: FUNCTION_NAME.to_string(),
-                    size: None,
+                    options: InjectionOptions::default(),
+                    mutation_opts: MutationOpts::default(),
                     global_opts: GlobalOpts {
                         source: PathBuf::from("test.wasm"),
                         destination: None
@@ -347,7 +720,7 @@ mod cli_tests {
     #[test]
     fn test_inject_infinite_loop() {
         assert_eq!(
-            Cli::try_parse_from(&[
+            Cli::try_parse_from([
                 "test",
                 "inject",
                 "infinite-loop",
@@ -359,7 +732,8 @@ mod cli_tests {
                 action: Action::Inject {
                     injection: Injection::InfiniteLoop,
                     function: FUNCTION_NAME.to_string(),
-                    size: None,
+                    options: InjectionOptions::default(),
+                    mutation_opts: MutationOpts::default(),
                     global_opts: GlobalOpts {
                         source: PathBuf::from("test.wasm"),
                         destination: None
@@ -372,32 +746,102 @@ mod cli_tests {
     }
 
     #[test]
-    fn test_inject_invalid_injection() {
-        let result = Cli::try_parse_from(&[
+    fn test_inject_unreachable() {
+        assert_eq!(
+            Cli::try_parse_from(["test", "inject", "unreachable", FUNCTION_NAME, "test.wasm"])
+                .unwrap(),
+            Cli {
+                action: Action::Inject {
+                    injection: Injection::Unreachable,
+                    function: FUNCTION_NAME.to_string(),
+                    options: InjectionOptions::default(),
+                    mutation_opts: MutationOpts::default(),
+                    global_opts: GlobalOpts {
+                        source: PathBuf::from("test.wasm"),
+                        destination: None
+                    },
+                    compressed: false,
+                    hexified: false
+                }
+            }
+        )
+    }
+
+    #[test]
+    fn test_inject_arithmetic_trap() {
+        assert_eq!(
+            Cli::try_parse_from([
+                "test",
+                "inject",
+                "arithmetic-trap",
+                "--trap",
+                "i32-div-by-zero",
+                FUNCTION_NAME,
+                "test.wasm"
+            ])
+            .unwrap(),
+            Cli {
+                action: Action::Inject {
+                    injection: Injection::ArithmeticTrap,
+                    function: FUNCTION_NAME.to_string(),
+                    options: InjectionOptions {
+                        trap: Some(ArithmeticTrap::I32DivByZero),
+                        ..Default::default()
+                    },
+                    mutation_opts: MutationOpts::default(),
+                    global_opts: GlobalOpts {
+                        source: PathBuf::from("test.wasm"),
+                        destination: None
+                    },
+                    compressed: false,
+                    hexified: false
+                }
+            }
+        )
+    }
+
+    #[test]
+    fn test_inject_arithmetic_trap_requires_trap_arg() {
+        let result = Cli::try_parse_from([
             "test",
             "inject",
-            "invalid-injection",
-            &FUNCTION_NAME,
+            "arithmetic-trap",
+            FUNCTION_NAME,
             "test.wasm",
         ]);
         assert!(result.is_err());
         assert_eq!(
             result.unwrap_err().kind(),
-            clap::error::ErrorKind::InvalidValue
+            clap::error::ErrorKind::MissingRequiredArgument
         )
     }
 
     #[test]
-    fn test_convert() {
+    fn test_inject_stack_exhaustion() {
         assert_eq!(
-            Cli::try_parse_from(&["test", "convert", "test.wasm"]).unwrap(),
+            Cli::try_parse_from([
+                "test",
+                "inject",
+                "stack-exhaustion",
+                "--locals",
+                "100",
+                FUNCTION_NAME,
+                "test.wasm"
+            ])
+            .unwrap(),
             Cli {
-                action: Action::Convert {
+                action: Action::Inject {
+                    injection: Injection::StackExhaustion,
+                    function: FUNCTION_NAME.to_string(),
+                    options: InjectionOptions {
+                        locals: Some(100),
+                        ..Default::default()
+                    },
+                    mutation_opts: MutationOpts::default(),
                     global_opts: GlobalOpts {
                         source: PathBuf::from("test.wasm"),
                         destination: None
                     },
-                    raw: true,
                     compressed: false,
                     hexified: false
                 }
@@ -406,9 +850,1078 @@ mod cli_tests {
     }
 
     #[test]
-    fn test_convert_raw_exludes_compressed() {
+    fn test_inject_memory_grow_exhaustion() {
+        assert_eq!(
+            Cli::try_parse_from([
+                "test",
+                "inject",
+                "memory-grow-exhaustion",
+                FUNCTION_NAME,
+                "test.wasm"
+            ])
+            .unwrap(),
+            Cli {
+                action: Action::Inject {
+                    injection: Injection::MemoryGrowExhaustion,
+                    function: FUNCTION_NAME.to_string(),
+                    options: InjectionOptions::default(),
+                    mutation_opts: MutationOpts::default(),
+                    global_opts: GlobalOpts {
+                        source: PathBuf::from("test.wasm"),
+                        destination: None
+                    },
+                    compressed: false,
+                    hexified: false
+                }
+            }
+        )
+    }
+
+    #[test]
+    fn test_inject_indirect_call_fault() {
+        assert_eq!(
+            Cli::try_parse_from([
+                "test",
+                "inject",
+                "indirect-call-fault",
+                "--indirect-call-fault",
+                "null-element",
+                FUNCTION_NAME,
+                "test.wasm"
+            ])
+            .unwrap(),
+            Cli {
+                action: Action::Inject {
+                    injection: Injection::IndirectCallFault,
+                    function: FUNCTION_NAME.to_string(),
+                    options: InjectionOptions {
+                        indirect_call_fault: Some(IndirectCallFault::NullElement),
+                        ..Default::default()
+                    },
+                    mutation_opts: MutationOpts::default(),
+                    global_opts: GlobalOpts {
+                        source: PathBuf::from("test.wasm"),
+                        destination: None
+                    },
+                    compressed: false,
+                    hexified: false
+                }
+            }
+        )
+    }
+
+    #[test]
+    fn test_inject_indirect_call_fault_requires_indirect_call_fault_arg() {
+        let result = Cli::try_parse_from([
+            "test",
+            "inject",
+            "indirect-call-fault",
+            FUNCTION_NAME,
+            "test.wasm",
+        ]);
+        assert!(result.is_err());
+        assert_eq!(
+            result.unwrap_err().kind(),
+            clap::error::ErrorKind::MissingRequiredArgument
+        )
+    }
+
+    #[test]
+    fn test_inject_bounded_loop() {
+        assert_eq!(
+            Cli::try_parse_from([
+                "test",
+                "inject",
+                "bounded-loop",
+                "--iterations",
+                "1000000",
+                FUNCTION_NAME,
+                "test.wasm"
+            ])
+            .unwrap(),
+            Cli {
+                action: Action::Inject {
+                    injection: Injection::BoundedLoop,
+                    function: FUNCTION_NAME.to_string(),
+                    options: InjectionOptions {
+                        iterations: Some(1_000_000),
+                        ..Default::default()
+                    },
+                    mutation_opts: MutationOpts::default(),
+                    global_opts: GlobalOpts {
+                        source: PathBuf::from("test.wasm"),
+                        destination: None
+                    },
+                    compressed: false,
+                    hexified: false
+                }
+            }
+        )
+    }
+
+    #[test]
+    fn test_inject_bounded_loop_requires_iterations_arg() {
         let result =
-            Cli::try_parse_from(&["test", "convert", "test.wasm", "--compressed", "--raw"]);
+            Cli::try_parse_from(["test", "inject", "bounded-loop", FUNCTION_NAME, "test.wasm"]);
+        assert!(result.is_err());
+        assert_eq!(
+            result.unwrap_err().kind(),
+            clap::error::ErrorKind::MissingRequiredArgument
+        )
+    }
+
+    #[test]
+    fn test_inject_bad_pointer_length() {
+        assert_eq!(
+            Cli::try_parse_from([
+                "test",
+                "inject",
+                "bad-pointer-length",
+                "--pointer-length-fault",
+                "zero-length",
+                FUNCTION_NAME,
+                "test.wasm"
+            ])
+            .unwrap(),
+            Cli {
+                action: Action::Inject {
+                    injection: Injection::BadPointerLength,
+                    function: FUNCTION_NAME.to_string(),
+                    options: InjectionOptions {
+                        pointer_length_fault: Some(PointerLengthFault::ZeroLength),
+                        ..Default::default()
+                    },
+                    mutation_opts: MutationOpts::default(),
+                    global_opts: GlobalOpts {
+                        source: PathBuf::from("test.wasm"),
+                        destination: None
+                    },
+                    compressed: false,
+                    hexified: false
+                }
+            }
+        )
+    }
+
+    #[test]
+    fn test_inject_bad_pointer_length_requires_pointer_length_fault_arg() {
+        let result = Cli::try_parse_from([
+            "test",
+            "inject",
+            "bad-pointer-length",
+            FUNCTION_NAME,
+            "test.wasm",
+        ]);
+        assert!(result.is_err());
+        assert_eq!(
+            result.unwrap_err().kind(),
+            clap::error::ErrorKind::MissingRequiredArgument
+        )
+    }
+
+    #[test]
+    fn test_inject_bad_validation_result() {
+        assert_eq!(
+            Cli::try_parse_from([
+                "test",
+                "inject",
+                "bad-validation-result",
+                "--validation-result-fault",
+                "hrmp-messages-out-of-order",
+                FUNCTION_NAME,
+                "test.wasm"
+            ])
+            .unwrap(),
+            Cli {
+                action: Action::Inject {
+                    injection: Injection::BadValidationResult,
+                    function: FUNCTION_NAME.to_string(),
+                    options: InjectionOptions {
+                        validation_result_fault: Some(
+                            ValidationResultFault::HrmpMessagesOutOfOrder
+                        ),
+                        ..Default::default()
+                    },
+                    mutation_opts: MutationOpts::default(),
+                    global_opts: GlobalOpts {
+                        source: PathBuf::from("test.wasm"),
+                        destination: None
+                    },
+                    compressed: false,
+                    hexified: false
+                }
+            }
+        )
+    }
+
+    #[test]
+    fn test_inject_bad_validation_result_upward_messages() {
+        assert_eq!(
+            Cli::try_parse_from([
+                "test",
+                "inject",
+                "bad-validation-result",
+                "--validation-result-fault",
+                "too-many-upward-messages",
+                "--upward-messages",
+                "1000",
+                FUNCTION_NAME,
+                "test.wasm"
+            ])
+            .unwrap(),
+            Cli {
+                action: Action::Inject {
+                    injection: Injection::BadValidationResult,
+                    function: FUNCTION_NAME.to_string(),
+                    options: InjectionOptions {
+                        validation_result_fault: Some(ValidationResultFault::TooManyUpwardMessages),
+                        upward_messages: Some(1000),
+                        ..Default::default()
+                    },
+                    mutation_opts: MutationOpts::default(),
+                    global_opts: GlobalOpts {
+                        source: PathBuf::from("test.wasm"),
+                        destination: None
+                    },
+                    compressed: false,
+                    hexified: false
+                }
+            }
+        )
+    }
+
+    #[test]
+    fn test_inject_bad_validation_result_requires_validation_result_fault_arg() {
+        let result = Cli::try_parse_from([
+            "test",
+            "inject",
+            "bad-validation-result",
+            FUNCTION_NAME,
+            "test.wasm",
+        ]);
+        assert!(result.is_err());
+        assert_eq!(
+            result.unwrap_err().kind(),
+            clap::error::ErrorKind::MissingRequiredArgument
+        )
+    }
+
+    #[test]
+    fn test_inject_host_function_misuse() {
+        assert_eq!(
+            Cli::try_parse_from([
+                "test",
+                "inject",
+                "host-function-misuse",
+                "--host-function",
+                "ext_storage_get_version_1",
+                "--host-function-misuse",
+                "pointer-beyond-memory",
+                FUNCTION_NAME,
+                "test.wasm"
+            ])
+            .unwrap(),
+            Cli {
+                action: Action::Inject {
+                    injection: Injection::HostFunctionMisuse,
+                    function: FUNCTION_NAME.to_string(),
+                    options: InjectionOptions {
+                        host_function: Some("ext_storage_get_version_1".to_string()),
+                        host_function_misuse: Some(HostFunctionMisuse::PointerBeyondMemory),
+                        ..Default::default()
+                    },
+                    mutation_opts: MutationOpts::default(),
+                    global_opts: GlobalOpts {
+                        source: PathBuf::from("test.wasm"),
+                        destination: None
+                    },
+                    compressed: false,
+                    hexified: false
+                }
+            }
+        )
+    }
+
+    #[test]
+    fn test_inject_host_function_misuse_requires_host_function_args() {
+        let result = Cli::try_parse_from([
+            "test",
+            "inject",
+            "host-function-misuse",
+            FUNCTION_NAME,
+            "test.wasm",
+        ]);
+        assert!(result.is_err());
+        assert_eq!(
+            result.unwrap_err().kind(),
+            clap::error::ErrorKind::MissingRequiredArgument
+        )
+    }
+
+    #[test]
+    fn test_inject_allocator_misuse() {
+        assert_eq!(
+            Cli::try_parse_from([
+                "test",
+                "inject",
+                "allocator-misuse",
+                "--allocator-misuse",
+                "double-free",
+                FUNCTION_NAME,
+                "test.wasm"
+            ])
+            .unwrap(),
+            Cli {
+                action: Action::Inject {
+                    injection: Injection::AllocatorMisuse,
+                    function: FUNCTION_NAME.to_string(),
+                    options: InjectionOptions {
+                        allocator_misuse: Some(AllocatorMisuse::DoubleFree),
+                        ..Default::default()
+                    },
+                    mutation_opts: MutationOpts::default(),
+                    global_opts: GlobalOpts {
+                        source: PathBuf::from("test.wasm"),
+                        destination: None
+                    },
+                    compressed: false,
+                    hexified: false
+                }
+            }
+        )
+    }
+
+    #[test]
+    fn test_inject_allocator_misuse_requires_allocator_misuse_arg() {
+        let result = Cli::try_parse_from([
+            "test",
+            "inject",
+            "allocator-misuse",
+            FUNCTION_NAME,
+            "test.wasm",
+        ]);
+        assert!(result.is_err());
+        assert_eq!(
+            result.unwrap_err().kind(),
+            clap::error::ErrorKind::MissingRequiredArgument
+        )
+    }
+
+    #[test]
+    fn test_inject_allocator_header_corruption() {
+        assert_eq!(
+            Cli::try_parse_from([
+                "test",
+                "inject",
+                "allocator-header-corruption",
+                "--header-corruption",
+                "bad-size-class",
+                FUNCTION_NAME,
+                "test.wasm"
+            ])
+            .unwrap(),
+            Cli {
+                action: Action::Inject {
+                    injection: Injection::AllocatorHeaderCorruption,
+                    function: FUNCTION_NAME.to_string(),
+                    options: InjectionOptions {
+                        header_corruption: Some(HeaderCorruption::BadSizeClass),
+                        ..Default::default()
+                    },
+                    mutation_opts: MutationOpts::default(),
+                    global_opts: GlobalOpts {
+                        source: PathBuf::from("test.wasm"),
+                        destination: None
+                    },
+                    compressed: false,
+                    hexified: false
+                }
+            }
+        )
+    }
+
+    #[test]
+    fn test_inject_allocator_header_corruption_requires_header_corruption_arg() {
+        let result = Cli::try_parse_from([
+            "test",
+            "inject",
+            "allocator-header-corruption",
+            FUNCTION_NAME,
+            "test.wasm",
+        ]);
+        assert!(result.is_err());
+        assert_eq!(
+            result.unwrap_err().kind(),
+            clap::error::ErrorKind::MissingRequiredArgument
+        )
+    }
+
+    #[test]
+    fn test_inject_allocator_fragmentation() {
+        assert_eq!(
+            Cli::try_parse_from([
+                "test",
+                "inject",
+                "allocator-fragmentation",
+                "--allocation-sizes",
+                "16,200,4096",
+                "--iterations",
+                "1000",
+                "--free-every",
+                "3",
+                FUNCTION_NAME,
+                "test.wasm"
+            ])
+            .unwrap(),
+            Cli {
+                action: Action::Inject {
+                    injection: Injection::AllocatorFragmentation,
+                    function: FUNCTION_NAME.to_string(),
+                    options: InjectionOptions {
+                        allocation_sizes: vec![16, 200, 4096],
+                        iterations: Some(1000),
+                        free_every: Some(3),
+                        ..Default::default()
+                    },
+                    mutation_opts: MutationOpts::default(),
+                    global_opts: GlobalOpts {
+                        source: PathBuf::from("test.wasm"),
+                        destination: None
+                    },
+                    compressed: false,
+                    hexified: false
+                }
+            }
+        )
+    }
+
+    #[test]
+    fn test_inject_allocator_fragmentation_requires_allocation_sizes_arg() {
+        let result = Cli::try_parse_from([
+            "test",
+            "inject",
+            "allocator-fragmentation",
+            FUNCTION_NAME,
+            "test.wasm",
+        ]);
+        assert!(result.is_err());
+        assert_eq!(
+            result.unwrap_err().kind(),
+            clap::error::ErrorKind::MissingRequiredArgument
+        )
+    }
+
+    #[test]
+    fn test_inject_storage_write_spam() {
+        assert_eq!(
+            Cli::try_parse_from([
+                "test",
+                "inject",
+                "storage-write-spam",
+                "--iterations",
+                "10000",
+                "--value-size",
+                "1024",
+                FUNCTION_NAME,
+                "test.wasm"
+            ])
+            .unwrap(),
+            Cli {
+                action: Action::Inject {
+                    injection: Injection::StorageWriteSpam,
+                    function: FUNCTION_NAME.to_string(),
+                    options: InjectionOptions {
+                        iterations: Some(10_000),
+                        value_size: Some(1024),
+                        ..Default::default()
+                    },
+                    mutation_opts: MutationOpts::default(),
+                    global_opts: GlobalOpts {
+                        source: PathBuf::from("test.wasm"),
+                        destination: None
+                    },
+                    compressed: false,
+                    hexified: false
+                }
+            }
+        )
+    }
+
+    #[test]
+    fn test_inject_storage_write_spam_requires_value_size_arg() {
+        let result = Cli::try_parse_from([
+            "test",
+            "inject",
+            "storage-write-spam",
+            FUNCTION_NAME,
+            "test.wasm",
+        ]);
+        assert!(result.is_err());
+        assert_eq!(
+            result.unwrap_err().kind(),
+            clap::error::ErrorKind::MissingRequiredArgument
+        )
+    }
+
+    #[test]
+    fn test_inject_storage_proof_miss() {
+        assert_eq!(
+            Cli::try_parse_from([
+                "test",
+                "inject",
+                "storage-proof-miss",
+                "--storage-key",
+                "0xdead",
+                FUNCTION_NAME,
+                "test.wasm"
+            ])
+            .unwrap(),
+            Cli {
+                action: Action::Inject {
+                    injection: Injection::StorageProofMiss,
+                    function: FUNCTION_NAME.to_string(),
+                    options: InjectionOptions {
+                        storage_key: Some("0xdead".to_string()),
+                        ..Default::default()
+                    },
+                    mutation_opts: MutationOpts::default(),
+                    global_opts: GlobalOpts {
+                        source: PathBuf::from("test.wasm"),
+                        destination: None
+                    },
+                    compressed: false,
+                    hexified: false
+                }
+            }
+        )
+    }
+
+    #[test]
+    fn test_inject_storage_proof_miss_without_storage_key() {
+        assert_eq!(
+            Cli::try_parse_from([
+                "test",
+                "inject",
+                "storage-proof-miss",
+                FUNCTION_NAME,
+                "test.wasm"
+            ])
+            .unwrap(),
+            Cli {
+                action: Action::Inject {
+                    injection: Injection::StorageProofMiss,
+                    function: FUNCTION_NAME.to_string(),
+                    options: InjectionOptions::default(),
+                    mutation_opts: MutationOpts::default(),
+                    global_opts: GlobalOpts {
+                        source: PathBuf::from("test.wasm"),
+                        destination: None
+                    },
+                    compressed: false,
+                    hexified: false
+                }
+            }
+        )
+    }
+
+    #[test]
+    fn test_inject_logging_spam() {
+        assert_eq!(
+            Cli::try_parse_from([
+                "test",
+                "inject",
+                "logging-spam",
+                "--message-size",
+                "4096",
+                "--log-level",
+                "warn",
+                FUNCTION_NAME,
+                "test.wasm"
+            ])
+            .unwrap(),
+            Cli {
+                action: Action::Inject {
+                    injection: Injection::LoggingSpam,
+                    function: FUNCTION_NAME.to_string(),
+                    options: InjectionOptions {
+                        message_size: Some(4096),
+                        log_level: Some(LogLevel::Warn),
+                        ..Default::default()
+                    },
+                    mutation_opts: MutationOpts::default(),
+                    global_opts: GlobalOpts {
+                        source: PathBuf::from("test.wasm"),
+                        destination: None
+                    },
+                    compressed: false,
+                    hexified: false
+                }
+            }
+        )
+    }
+
+    #[test]
+    fn test_inject_logging_spam_requires_log_level_arg() {
+        let result =
+            Cli::try_parse_from(["test", "inject", "logging-spam", FUNCTION_NAME, "test.wasm"]);
+        assert!(result.is_err());
+        assert_eq!(
+            result.unwrap_err().kind(),
+            clap::error::ErrorKind::MissingRequiredArgument
+        )
+    }
+
+    #[test]
+    fn test_inject_host_cpu_burn() {
+        assert_eq!(
+            Cli::try_parse_from([
+                "test",
+                "inject",
+                "host-cpu-burn",
+                "--host-cpu-burn",
+                "sr25519-verify",
+                "--iterations",
+                "1000",
+                "--buffer-size",
+                "1048576",
+                FUNCTION_NAME,
+                "test.wasm"
+            ])
+            .unwrap(),
+            Cli {
+                action: Action::Inject {
+                    injection: Injection::HostCpuBurn,
+                    function: FUNCTION_NAME.to_string(),
+                    options: InjectionOptions {
+                        host_cpu_burn: Some(HostCpuBurn::Sr25519Verify),
+                        iterations: Some(1000),
+                        buffer_size: Some(1048576),
+                        ..Default::default()
+                    },
+                    mutation_opts: MutationOpts::default(),
+                    global_opts: GlobalOpts {
+                        source: PathBuf::from("test.wasm"),
+                        destination: None
+                    },
+                    compressed: false,
+                    hexified: false
+                }
+            }
+        )
+    }
+
+    #[test]
+    fn test_inject_host_cpu_burn_requires_buffer_size_arg() {
+        let result = Cli::try_parse_from([
+            "test",
+            "inject",
+            "host-cpu-burn",
+            FUNCTION_NAME,
+            "test.wasm",
+        ]);
+        assert!(result.is_err());
+        assert_eq!(
+            result.unwrap_err().kind(),
+            clap::error::ErrorKind::MissingRequiredArgument
+        )
+    }
+
+    #[test]
+    fn test_inject_forbidden_host_call() {
+        assert_eq!(
+            Cli::try_parse_from([
+                "test",
+                "inject",
+                "forbidden-host-call",
+                "--forbidden-host-function",
+                "http-request-start",
+                FUNCTION_NAME,
+                "test.wasm"
+            ])
+            .unwrap(),
+            Cli {
+                action: Action::Inject {
+                    injection: Injection::ForbiddenHostCall,
+                    function: FUNCTION_NAME.to_string(),
+                    options: InjectionOptions {
+                        forbidden_host_function: Some(ForbiddenHostFunction::HttpRequestStart),
+                        ..Default::default()
+                    },
+                    mutation_opts: MutationOpts::default(),
+                    global_opts: GlobalOpts {
+                        source: PathBuf::from("test.wasm"),
+                        destination: None
+                    },
+                    compressed: false,
+                    hexified: false
+                }
+            }
+        )
+    }
+
+    #[test]
+    fn test_inject_forbidden_host_call_requires_forbidden_host_function_arg() {
+        let result = Cli::try_parse_from([
+            "test",
+            "inject",
+            "forbidden-host-call",
+            FUNCTION_NAME,
+            "test.wasm",
+        ]);
+        assert!(result.is_err());
+        assert_eq!(
+            result.unwrap_err().kind(),
+            clap::error::ErrorKind::MissingRequiredArgument
+        )
+    }
+
+    #[test]
+    fn test_inject_runtime_panic() {
+        assert_eq!(
+            Cli::try_parse_from([
+                "test",
+                "inject",
+                "runtime-panic",
+                "--panic-message",
+                "Injected panic",
+                FUNCTION_NAME,
+                "test.wasm"
+            ])
+            .unwrap(),
+            Cli {
+                action: Action::Inject {
+                    injection: Injection::RuntimePanic,
+                    function: FUNCTION_NAME.to_string(),
+                    options: InjectionOptions {
+                        panic_message: Some("Injected panic".to_string()),
+                        ..Default::default()
+                    },
+                    mutation_opts: MutationOpts::default(),
+                    global_opts: GlobalOpts {
+                        source: PathBuf::from("test.wasm"),
+                        destination: None
+                    },
+                    compressed: false,
+                    hexified: false
+                }
+            }
+        )
+    }
+
+    #[test]
+    fn test_inject_runtime_panic_requires_panic_message_arg() {
+        let result = Cli::try_parse_from([
+            "test",
+            "inject",
+            "runtime-panic",
+            FUNCTION_NAME,
+            "test.wasm",
+        ]);
+        assert!(result.is_err());
+        assert_eq!(
+            result.unwrap_err().kind(),
+            clap::error::ErrorKind::MissingRequiredArgument
+        )
+    }
+
+    #[test]
+    fn test_inject_stack_pointer_corruption() {
+        assert_eq!(
+            Cli::try_parse_from([
+                "test",
+                "inject",
+                "stack-pointer-corruption",
+                "--stack-pointer-corruption",
+                "below-data-end",
+                FUNCTION_NAME,
+                "test.wasm"
+            ])
+            .unwrap(),
+            Cli {
+                action: Action::Inject {
+                    injection: Injection::StackPointerCorruption,
+                    function: FUNCTION_NAME.to_string(),
+                    options: InjectionOptions {
+                        stack_pointer_corruption: Some(StackPointerCorruption::BelowDataEnd),
+                        ..Default::default()
+                    },
+                    mutation_opts: MutationOpts::default(),
+                    global_opts: GlobalOpts {
+                        source: PathBuf::from("test.wasm"),
+                        destination: None
+                    },
+                    compressed: false,
+                    hexified: false
+                }
+            }
+        )
+    }
+
+    #[test]
+    fn test_inject_stack_pointer_corruption_requires_stack_pointer_corruption_arg() {
+        let result = Cli::try_parse_from([
+            "test",
+            "inject",
+            "stack-pointer-corruption",
+            FUNCTION_NAME,
+            "test.wasm",
+        ]);
+        assert!(result.is_err());
+        assert_eq!(
+            result.unwrap_err().kind(),
+            clap::error::ErrorKind::MissingRequiredArgument
+        )
+    }
+
+    #[test]
+    fn test_inject_out_of_bounds_access() {
+        assert_eq!(
+            Cli::try_parse_from([
+                "test",
+                "inject",
+                "out-of-bounds-access",
+                "--address",
+                "1245184",
+                "--offset",
+                "0",
+                "--width",
+                "32",
+                "--access",
+                "load",
+                "--alignment",
+                "1",
+                FUNCTION_NAME,
+                "test.wasm"
+            ])
+            .unwrap(),
+            Cli {
+                action: Action::Inject {
+                    injection: Injection::OutOfBoundsAccess,
+                    function: FUNCTION_NAME.to_string(),
+                    options: InjectionOptions {
+                        address: Some(1245184),
+                        offset: Some(0),
+                        width: Some(AccessWidth::Bits32),
+                        access: Some(MemoryAccess::Load),
+                        alignment: Some(1),
+                        ..Default::default()
+                    },
+                    mutation_opts: MutationOpts::default(),
+                    global_opts: GlobalOpts {
+                        source: PathBuf::from("test.wasm"),
+                        destination: None
+                    },
+                    compressed: false,
+                    hexified: false
+                }
+            }
+        )
+    }
+
+    #[test]
+    fn test_inject_out_of_bounds_access_requires_access_arg() {
+        let result = Cli::try_parse_from([
+            "test",
+            "inject",
+            "out-of-bounds-access",
+            FUNCTION_NAME,
+            "test.wasm",
+        ]);
+        assert!(result.is_err());
+        assert_eq!(
+            result.unwrap_err().kind(),
+            clap::error::ErrorKind::MissingRequiredArgument
+        )
+    }
+
+    #[test]
+    fn test_inject_invalid_injection() {
+        let result = Cli::try_parse_from([
+            "test",
+            "inject",
+            "invalid-injection",
+            FUNCTION_NAME,
+            "test.wasm",
+        ]);
+        assert!(result.is_err());
+        assert_eq!(
+            result.unwrap_err().kind(),
+            clap::error::ErrorKind::InvalidValue
+        )
+    }
+
+    #[test]
+    fn test_convert() {
+        assert_eq!(
+            Cli::try_parse_from(["test", "convert", "test.wasm"]).unwrap(),
+            Cli {
+                action: Action::Convert {
+                    mutation_opts: MutationOpts::default(),
+                    global_opts: GlobalOpts {
+                        source: PathBuf::from("test.wasm"),
+                        destination: None
+                    },
+                    raw: true,
+                    compressed: false,
+                    hexified: false
+                }
+            }
+        )
+    }
+
+    #[test]
+    fn test_convert_memory_limits() {
+        assert_eq!(
+            Cli::try_parse_from([
+                "test",
+                "convert",
+                "--initial-pages",
+                "65537",
+                "--remove-maximum-pages",
+                "test.wasm"
+            ])
+            .unwrap(),
+            Cli {
+                action: Action::Convert {
+                    mutation_opts: MutationOpts {
+                        initial_pages: Some(65537),
+                        remove_maximum_pages: true,
+                        ..Default::default()
+                    },
+                    global_opts: GlobalOpts {
+                        source: PathBuf::from("test.wasm"),
+                        destination: None
+                    },
+                    raw: true,
+                    compressed: false,
+                    hexified: false
+                }
+            }
+        )
+    }
+
+    #[test]
+    fn test_convert_memory_declaration() {
+        assert_eq!(
+            Cli::try_parse_from([
+                "test",
+                "convert",
+                "--memory-declaration",
+                "exported",
+                "test.wasm"
+            ])
+            .unwrap(),
+            Cli {
+                action: Action::Convert {
+                    mutation_opts: MutationOpts {
+                        memory_declaration: Some(MemoryDeclaration::Exported),
+                        ..Default::default()
+                    },
+                    global_opts: GlobalOpts {
+                        source: PathBuf::from("test.wasm"),
+                        destination: None
+                    },
+                    raw: true,
+                    compressed: false,
+                    hexified: false
+                }
+            }
+        )
+    }
+
+    #[test]
+    fn test_inject_heap_base_tampering() {
+        assert_eq!(
+            Cli::try_parse_from([
+                "test",
+                "inject",
+                "unreachable",
+                "--heap-base-tampering",
+                "inside-stack",
+                FUNCTION_NAME,
+                "test.wasm"
+            ])
+            .unwrap(),
+            Cli {
+                action: Action::Inject {
+                    injection: Injection::Unreachable,
+                    options: InjectionOptions::default(),
+                    function: FUNCTION_NAME.to_string(),
+                    mutation_opts: MutationOpts {
+                        heap_base_tampering: Some(HeapBaseTampering::InsideStack),
+                        ..Default::default()
+                    },
+                    global_opts: GlobalOpts {
+                        source: PathBuf::from("test.wasm"),
+                        destination: None
+                    },
+                    compressed: false,
+                    hexified: false
+                }
+            }
+        )
+    }
+
+    #[test]
+    fn test_global() {
+        assert_eq!(
+            Cli::try_parse_from([
+                "test",
+                "global",
+                "__stack_pointer",
+                "--value",
+                "-1",
+                "--mutable",
+                "false",
+                "test.wasm"
+            ])
+            .unwrap(),
+            Cli {
+                action: Action::Global {
+                    global: "__stack_pointer".to_string(),
+                    value: Some("-1".to_string()),
+                    mutable: Some(false),
+                    global_opts: GlobalOpts {
+                        source: PathBuf::from("test.wasm"),
+                        destination: None
+                    },
+                    compressed: false,
+                    hexified: false
+                }
+            }
+        )
+    }
+
+    #[test]
+    fn test_global_requires_value_or_mutable_arg() {
+        let result = Cli::try_parse_from(["test", "global", "0", "test.wasm"]);
+        assert!(result.is_err());
+        assert_eq!(
+            result.unwrap_err().kind(),
+            clap::error::ErrorKind::MissingRequiredArgument
+        );
+    }
+
+    #[test]
+    fn test_remove_maximum_pages_excludes_maximum_pages() {
+        let result = Cli::try_parse_from([
+            "test",
+            "convert",
+            "--maximum-pages",
+            "1",
+            "--remove-maximum-pages",
+            "test.wasm",
+        ]);
+        assert!(result.is_err());
+        assert_eq!(
+            result.unwrap_err().kind(),
+            clap::error::ErrorKind::ArgumentConflict
+        );
+    }
+
+    #[test]
+    fn test_convert_raw_exludes_compressed() {
+        let result = Cli::try_parse_from(["test", "convert", "test.wasm", "--compressed", "--raw"]);
         assert!(result.is_err());
         assert_eq!(
             result.unwrap_err().kind(),
@@ -418,7 +1931,7 @@ mod cli_tests {
 
     #[test]
     fn test_convert_raw_exludes_hexified() {
-        let result = Cli::try_parse_from(&["test", "convert", "test.wasm", "--hexified", "--raw"]);
+        let result = Cli::try_parse_from(["test", "convert", "test.wasm", "--hexified", "--raw"]);
         assert!(result.is_err());
         assert_eq!(
             result.unwrap_err().kind(),